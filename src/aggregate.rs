@@ -0,0 +1,55 @@
+#![warn(missing_docs)]
+
+use std::net::Ipv4Addr;
+
+use crate::{Cidr, Network};
+
+/// Collapse networks into the minimal sorted list of Networks covering exactly the same addresses.
+/// Adjacent sibling networks are merged into their supernet, and networks contained in another network are dropped.
+pub fn collapse_networks<I: IntoIterator<Item = Network>>(networks: I) -> Vec<Network> {
+    let ranges = networks
+        .into_iter()
+        .map(|network| (u32::from(network.network_id()), network.last_address_u32()))
+        .collect();
+
+    merge_ranges(ranges)
+        .into_iter()
+        .flat_map(|(first, last)| range_to_networks(first, last))
+        .collect()
+}
+
+/// Sort inclusive address ranges and merge those that overlap or are adjacent.
+pub(crate) fn merge_ranges(mut ranges: Vec<(u32, u32)>) -> Vec<(u32, u32)> {
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(u32, u32)> = Vec::with_capacity(ranges.len());
+    for (first, last) in ranges {
+        match merged.last_mut() {
+            Some((_, merged_last)) if first as u64 <= *merged_last as u64 + 1 => {
+                *merged_last = (*merged_last).max(last);
+            }
+            _ => merged.push((first, last)),
+        }
+    }
+    merged
+}
+
+/// Return the minimal list of Networks covering the inclusive address range from first to last.
+pub(crate) fn range_to_networks(first: u32, last: u32) -> Vec<Network> {
+    let mut networks = Vec::new();
+    let mut start = first as u64;
+    let end = last as u64 + 1;
+    while start < end {
+        // The largest block that starts on a boundary of its own size and does not pass the end of the range.
+        let alignment_bits = start.trailing_zeros().min(32);
+        let length_bits = 63 - (end - start).leading_zeros();
+        let host_bits = alignment_bits.min(length_bits);
+
+        let cidr = Cidr::new(32 - host_bits as u8).expect("host bits are at most 32");
+        let network =
+            Network::new(Ipv4Addr::from(start as u32), cidr).expect("Network::new never fails");
+        networks.push(network);
+        start += 1 << host_bits;
+    }
+    networks
+}
@@ -0,0 +1,552 @@
+//! HTTP server exposing the subnet library as a JSON API.
+//!
+//! Endpoints, all using GET with query string parameters:
+//!   /network?network=10.1.2.3/20               Information about a network
+//!   /slsm?network=10.1.0.0/20&cidr=24          Split a network into subnets of the given CIDR
+//!   /vlsm?network=10.1.0.0/20&hosts=1000,200   Allocate subnets for each required host count and report the free space
+//!
+//! A network may also be given as "ip_address netmask", e.g. network=10.1.2.3+255.255.240.0.
+
+use std::env;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use subnet::{Cidr, Network, NetworkError, Slsm, Vlsm};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+/// Largest number of subnets returned by a single request.
+const MAX_SUBNETS: u64 = 65536;
+
+/// Largest number of required host counts accepted by the VLSM endpoint.
+const MAX_REQUIRED_HOSTS: usize = 1024;
+
+/// Longest request line or header accepted, in bytes.
+const MAX_LINE_LENGTH: u64 = 8192;
+
+/// Largest number of headers accepted in a request.
+const MAX_HEADERS: usize = 100;
+
+/// Largest number of connections handled at the same time. Further connections are refused with a 503.
+const MAX_CONNECTIONS: usize = 64;
+
+/// How long a connection may wait to send its request or receive its response.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An HTTP response with a JSON body.
+#[derive(Debug, PartialEq, Eq)]
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, code: &str, message: &str) -> Self {
+        Self {
+            status,
+            body: format!(
+                "{{\"error\":{{\"code\":{},\"message\":{}}}}}",
+                json_string(code),
+                json_string(message)
+            ),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            422 => "Unprocessable Entity",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+impl From<NetworkError> for Response {
+    fn from(err: NetworkError) -> Self {
+        let status = match err {
+            NetworkError::CidrOutOfRangeError
+            | NetworkError::IPv4AddressError
+            | NetworkError::IPv6AddressError
+            | NetworkError::ParsingError
+            | NetworkError::InvalidRange => 400,
+            _ => 422,
+        };
+        Response::error(status, &error_code(&err), &err.to_string())
+    }
+}
+
+/// Return the name of the NetworkError variant, taken from its derived Debug output.
+fn error_code(err: &NetworkError) -> String {
+    format!("{err:?}")
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn main() -> ExitCode {
+    let address = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+    let listener = match TcpListener::bind(&address) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("error: could not listen on {address}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("listening on http://{address}");
+
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    let busy =
+                        Response::error(503, "ServiceUnavailable", "too many open connections");
+                    if let Err(err) = write_response(stream, &busy) {
+                        eprintln!("error: {err}");
+                    }
+                    continue;
+                }
+                let connection = Connection(Arc::clone(&active));
+                thread::spawn(move || {
+                    let _connection = connection;
+                    if let Err(err) = handle_connection(stream) {
+                        eprintln!("error: {err}");
+                    }
+                });
+            }
+            Err(err) => eprintln!("error: {err}"),
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Counts a connection as active until it is dropped.
+struct Connection(Arc<AtomicUsize>);
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Read a single request from the stream and write the response.
+fn handle_connection(stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let response = match read_request_line(&mut reader)? {
+        Some(request_line) => {
+            let mut parts = request_line.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some(method), Some(target)) => handle_request(method, target),
+                _ => Response::error(400, "BadRequest", "malformed request line"),
+            }
+        }
+        None => Response::error(
+            431,
+            "RequestTooLarge",
+            &format!(
+                "lines are limited to {MAX_LINE_LENGTH} bytes and requests to {MAX_HEADERS} headers"
+            ),
+        ),
+    };
+    write_response(stream, &response)
+}
+
+/// Read the request line and skip the headers, as the API has no use for them.
+/// Return None if a line is longer than MAX_LINE_LENGTH or there are more than MAX_HEADERS headers.
+fn read_request_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let Some(request_line) = read_line(reader)? else {
+        return Ok(None);
+    };
+    for _ in 0..=MAX_HEADERS {
+        match read_line(reader)? {
+            Some(header) if header.trim_end().is_empty() => return Ok(Some(request_line)),
+            Some(_) => {}
+            None => return Ok(None),
+        }
+    }
+    Ok(None)
+}
+
+/// Read a line of at most MAX_LINE_LENGTH bytes, returning None if the line is longer.
+/// The end of the stream reads as an empty line.
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    reader.take(MAX_LINE_LENGTH).read_line(&mut line)?;
+    if line.len() as u64 == MAX_LINE_LENGTH && !line.ends_with('\n') {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+/// Write the response to the stream and close the connection.
+fn write_response(mut stream: TcpStream, response: &Response) -> io::Result<()> {
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        response.reason(),
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+/// Route a request to its endpoint.
+fn handle_request(method: &str, target: &str) -> Response {
+    if method != "GET" {
+        return Response::error(405, "MethodNotAllowed", "only GET is supported");
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let params = parse_query(query);
+    let result = match path {
+        "/network" => network_info(&params),
+        "/slsm" => slsm(&params),
+        "/vlsm" => vlsm(&params),
+        _ => return Response::error(404, "NotFound", "unknown endpoint"),
+    };
+    match result {
+        Ok(body) => Response::ok(body),
+        Err(response) => response,
+    }
+}
+
+fn network_info(params: &[(String, String)]) -> Result<String, Response> {
+    let network = network_param(params)?;
+    Ok(network_json(&network))
+}
+
+fn slsm(params: &[(String, String)]) -> Result<String, Response> {
+    let network = network_param(params)?;
+    let cidr = param(params, "cidr")?
+        .parse::<u8>()
+        .map_err(|_| Response::error(400, "InvalidParameter", "cidr must be a number"))?;
+    let cidr = Cidr::new(cidr)?;
+    if cidr.saturating_sub(*network.cidr()) > MAX_SUBNETS.trailing_zeros() as u8 {
+        return Err(Response::error(
+            422,
+            "TooManySubnets",
+            &format!("at most {MAX_SUBNETS} subnets can be returned"),
+        ));
+    }
+    let slsm = Slsm::new(network.clone(), cidr)?;
+    Ok(format!(
+        "{{\"base_network\":{},\"cidr\":{},\"subnets\":{}}}",
+        json_string(&network.to_string()),
+        *cidr,
+        subnets_json(slsm)
+    ))
+}
+
+fn vlsm(params: &[(String, String)]) -> Result<String, Response> {
+    let network = network_param(params)?;
+    let required_hosts = param(params, "hosts")?
+        .split(',')
+        .map(|hosts| hosts.trim().parse::<u32>())
+        .collect::<Result<Vec<u32>, _>>()
+        .map_err(|_| {
+            Response::error(
+                400,
+                "InvalidParameter",
+                "hosts must be a comma separated list of numbers",
+            )
+        })?;
+    if required_hosts.len() > MAX_REQUIRED_HOSTS {
+        return Err(Response::error(
+            422,
+            "TooManyRequirements",
+            &format!("at most {MAX_REQUIRED_HOSTS} host counts can be given"),
+        ));
+    }
+    let report = Vlsm::new(network.clone(), required_hosts)?.report();
+    let free: Vec<String> = report
+        .free()
+        .iter()
+        .map(|block| json_string(&block.to_string()))
+        .collect();
+    Ok(format!(
+        "{{\"base_network\":{},\"subnets\":{},\"free\":[{}],\"rounding_waste\":{},\"utilization\":{}}}",
+        json_string(&network.to_string()),
+        subnets_json(report.allocated().iter().cloned()),
+        free.join(","),
+        report.rounding_waste(),
+        report.utilization()
+    ))
+}
+
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Result<&'a str, Response> {
+    params
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+        .ok_or_else(|| {
+            Response::error(
+                400,
+                "MissingParameter",
+                &format!("missing parameter {name}"),
+            )
+        })
+}
+
+fn network_param(params: &[(String, String)]) -> Result<Network, Response> {
+    Ok(Network::try_from(param(params, "network")?)?)
+}
+
+fn network_json(network: &Network) -> String {
+    format!(
+        "{{\"network\":{},\"network_id\":{},\"cidr\":{},\"netmask\":{},\"broadcast\":{},\"first_host\":{},\"last_host\":{},\"hosts\":{}}}",
+        json_string(&network.to_string()),
+        json_string(&network.network_id().to_string()),
+        *network.cidr(),
+        json_string(&network.netmask().to_string()),
+        json_option(network.broadcast_address()),
+        json_option(network.first_host_address()),
+        json_option(network.last_host_address()),
+        network.number_of_hosts()
+    )
+}
+
+fn subnets_json(subnets: impl Iterator<Item = Network>) -> String {
+    let subnets: Vec<String> = subnets.map(|subnet| network_json(&subnet)).collect();
+    format!("[{}]", subnets.join(","))
+}
+
+fn json_option<T: ToString>(value: Option<T>) -> String {
+    match value {
+        Some(value) => json_string(&value.to_string()),
+        None => "null".to_string(),
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Split a query string into decoded key value pairs.
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+/// Decode "+" as a space and "%XX" escapes as bytes.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_endpoint() {
+        let want = Response::ok(
+            "{\"network\":\"10.1.0.0/20\",\"network_id\":\"10.1.0.0\",\"cidr\":20,\"netmask\":\"255.255.240.0\",\"broadcast\":\"10.1.15.255\",\"first_host\":\"10.1.0.1\",\"last_host\":\"10.1.15.254\",\"hosts\":4094}".to_string(),
+        );
+        assert_eq!(handle_request("GET", "/network?network=10.1.2.3/20"), want);
+        assert_eq!(
+            handle_request("GET", "/network?network=10.1.2.3%2F20"),
+            want
+        );
+        assert_eq!(
+            handle_request("GET", "/network?network=10.1.2.3+255.255.240.0"),
+            want
+        );
+
+        let got = handle_request("GET", "/network?network=10.1.2.3/32");
+        assert!(got.body.contains("\"broadcast\":null"));
+
+        assert_eq!(
+            handle_request("GET", "/network?network=10.1.2.3/33"),
+            Response::error(
+                400,
+                "CidrOutOfRangeError",
+                "CIDR is out of range for the address family"
+            )
+        );
+        assert_eq!(
+            handle_request("GET", "/network?network=10.1.2/24"),
+            Response::error(400, "IPv4AddressError", "invalid IPv4 address")
+        );
+        assert_eq!(
+            handle_request("GET", "/network?network=10.1.2.3"),
+            Response::error(400, "ParsingError", "malformed network string")
+        );
+        assert_eq!(
+            handle_request("GET", "/network"),
+            Response::error(400, "MissingParameter", "missing parameter network")
+        );
+        assert_eq!(handle_request("POST", "/network").status, 405);
+        assert_eq!(handle_request("GET", "/unknown").status, 404);
+    }
+
+    #[test]
+    fn slsm_endpoint() {
+        let got = handle_request("GET", "/slsm?network=10.0.0.0/24&cidr=25");
+        assert_eq!(got.status, 200);
+        assert!(got
+            .body
+            .starts_with("{\"base_network\":\"10.0.0.0/24\",\"cidr\":25,\"subnets\":[{\"network\":\"10.0.0.0/25\","));
+        assert!(got.body.contains("{\"network\":\"10.0.0.128/25\","));
+
+        assert_eq!(
+            handle_request("GET", "/slsm?network=10.0.0.0/24&cidr=23"),
+            Response::error(
+                422,
+                "InvalidSubnetCidr",
+                "subnet CIDR is less than the network CIDR"
+            )
+        );
+        assert_eq!(
+            handle_request("GET", "/slsm?network=0.0.0.0/0&cidr=32").status,
+            422
+        );
+        assert_eq!(
+            handle_request("GET", "/slsm?network=10.0.0.0/24&cidr=x").status,
+            400
+        );
+    }
+
+    #[test]
+    fn vlsm_endpoint() {
+        let got = handle_request("GET", "/vlsm?network=10.0.0.0/24&hosts=60,100");
+        assert_eq!(got.status, 200);
+        assert!(got.body.starts_with(
+            "{\"base_network\":\"10.0.0.0/24\",\"subnets\":[{\"network\":\"10.0.0.0/25\","
+        ));
+        assert!(got.body.contains("{\"network\":\"10.0.0.128/26\","));
+        assert!(got
+            .body
+            .ends_with("],\"free\":[\"10.0.0.192/26\"],\"rounding_waste\":28,\"utilization\":75}"));
+
+        assert_eq!(
+            handle_request("GET", "/vlsm?network=10.0.0.0/24&hosts=200,100"),
+            Response {
+                status: 422,
+                body: "{\"error\":{\"code\":\"InsufficientAddressSpace\",\"message\":\"requirement 1 for 100 hosts does not fit: 384 addresses required, 256 available\"}}".to_string(),
+            }
+        );
+        assert_eq!(
+            handle_request("GET", "/vlsm?network=10.0.0.0/24&hosts=60,-1").status,
+            400
+        );
+        let hosts = vec!["1"; MAX_REQUIRED_HOSTS + 1].join(",");
+        assert_eq!(
+            handle_request("GET", &format!("/vlsm?network=0.0.0.0/0&hosts={hosts}")),
+            Response::error(
+                422,
+                "TooManyRequirements",
+                "at most 1024 host counts can be given"
+            )
+        );
+    }
+
+    #[test]
+    fn read_request() {
+        let request = |s: String| read_request_line(&mut io::Cursor::new(s)).unwrap();
+
+        assert_eq!(
+            request("GET /network HTTP/1.1\r\nHost: localhost\r\n\r\n".to_string()),
+            Some("GET /network HTTP/1.1\r\n".to_string())
+        );
+        // A connection closed after the request line still gets a response.
+        assert_eq!(
+            request("GET /network HTTP/1.1\r\n".to_string()),
+            Some("GET /network HTTP/1.1\r\n".to_string())
+        );
+
+        let long_line = "a".repeat(MAX_LINE_LENGTH as usize);
+        assert_eq!(request(format!("GET /{long_line} HTTP/1.1\r\n\r\n")), None);
+        assert_eq!(
+            request(format!("GET / HTTP/1.1\r\nX-Long: {long_line}\r\n\r\n")),
+            None
+        );
+
+        let headers = "X-Header: 1\r\n".repeat(MAX_HEADERS);
+        assert_eq!(
+            request(format!("GET / HTTP/1.1\r\n{headers}\r\n")),
+            Some("GET / HTTP/1.1\r\n".to_string())
+        );
+        assert_eq!(
+            request(format!("GET / HTTP/1.1\r\n{headers}X-Header: 1\r\n\r\n")),
+            None
+        );
+    }
+
+    #[test]
+    fn decode_query() {
+        assert_eq!(
+            parse_query("network=10.0.0.0%2f8&hosts=1%2C2&flag"),
+            vec![
+                ("network".to_string(), "10.0.0.0/8".to_string()),
+                ("hosts".to_string(), "1,2".to_string()),
+                ("flag".to_string(), "".to_string()),
+            ]
+        );
+        assert_eq!(percent_decode("a+b%20c%"), "a b c%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+        assert_eq!(percent_decode("%+1"), "% 1");
+        assert_eq!(percent_decode("%-1x"), "%-1x");
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\u000a\"");
+    }
+}
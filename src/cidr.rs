@@ -5,7 +5,7 @@ use std::ops::Deref;
 use crate::NetworkError;
 
 /// Holds CIDR value for subnet
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
 pub struct Cidr {
     inner: u8,
 }
@@ -18,6 +18,39 @@ impl Cidr {
         }
         Ok(Self { inner: cidr })
     }
+
+    /// Return the largest Cidr whose Networks have room for the required number of hosts.
+    /// Follows the conventions of Network::number_of_hosts, so 0 hosts need a /32 and 1 or 2 hosts need a /31.
+    /// Return a HostCountOutOfRange error if even a /0 Network has too few hosts.
+    pub fn for_host_count(hosts: u32) -> Result<Self, NetworkError> {
+        let host_bits = match hosts {
+            0 => 0,
+            1 | 2 => 1,
+            _ => (hosts as u64 + 2).next_power_of_two().trailing_zeros(),
+        };
+        if host_bits > 32 {
+            return Err(NetworkError::HostCountOutOfRange {
+                hosts: hosts as u64,
+            });
+        }
+        Cidr::new(32 - host_bits as u8)
+    }
+
+    /// Return the largest Cidr that splits a Network with the base Cidr into at least the required number of subnets.
+    /// Return a SubnetCountOutOfRange error if even /32 subnets are too few.
+    pub fn for_subnet_count(base: Cidr, subnets: u64) -> Result<Self, NetworkError> {
+        let subnet_bits = match subnets.max(1).checked_next_power_of_two() {
+            Some(subnets) => subnets.trailing_zeros(),
+            None => u64::BITS,
+        };
+        if subnet_bits > 32 - base.inner as u32 {
+            return Err(NetworkError::SubnetCountOutOfRange {
+                cidr: base.inner,
+                subnets,
+            });
+        }
+        Cidr::new(base.inner + subnet_bits as u8)
+    }
 }
 
 impl Deref for Cidr {
@@ -45,3 +78,35 @@ impl Cidr {
         mask << (32 - cidr_value)
     }
 }
+
+/// Holds CIDR value for an IPv6 subnet
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+pub struct Ipv6Cidr {
+    inner: u8,
+}
+
+impl Ipv6Cidr {
+    /// Create and initialise a new Ipv6Cidr struct
+    pub fn new(cidr: u8) -> Result<Self, NetworkError> {
+        if cidr > 128 {
+            return Err(NetworkError::CidrOutOfRangeError);
+        }
+        Ok(Self { inner: cidr })
+    }
+}
+
+impl Deref for Ipv6Cidr {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl Ipv6Cidr {
+    /// Generates u128 from cidr.
+    /// The Cidr most significant bits of returned u128 are set to one, the rest are set to zero.
+    pub(crate) fn to_bitmask(self) -> u128 {
+        u128::MAX.checked_shl(128 - self.inner as u32).unwrap_or(0)
+    }
+}
@@ -0,0 +1,115 @@
+#![warn(missing_docs)]
+
+use std::fmt;
+use std::net::IpAddr;
+
+use crate::{Ipv6Network, Network, NetworkError, Prefix};
+
+/// Represents either an IPv4 or an IPv6 Network.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum IpNetwork {
+    /// An IPv4 Network
+    V4(Network),
+    /// An IPv6 Network
+    V6(Ipv6Network),
+}
+
+impl IpNetwork {
+    /// Create and initialise a new IpNetwork. Takes a std::net::IpAddr and a Prefix.
+    /// Return an error if the Prefix is too long for the address family.
+    pub fn new(ip_address: IpAddr, prefix: Prefix) -> Result<Self, NetworkError> {
+        match ip_address {
+            IpAddr::V4(ip_address) => Ok(Self::V4(Network::new(ip_address, prefix.try_into()?)?)),
+            IpAddr::V6(ip_address) => {
+                Ok(Self::V6(Ipv6Network::new(ip_address, prefix.try_into()?)?))
+            }
+        }
+    }
+
+    /// Return the first address in the subnet, this is the Network Id.
+    pub fn first_address(&self) -> IpAddr {
+        self.network_id()
+    }
+
+    /// Return the last address in the subnet. For IPv4 this is the broadcast address.
+    pub fn last_address(&self) -> IpAddr {
+        match self {
+            Self::V4(network) => match network.broadcast_address() {
+                Some(broadcast) => IpAddr::V4(broadcast),
+                None => IpAddr::V4(network.network_id()),
+            },
+            Self::V6(network) => IpAddr::V6(network.last_address()),
+        }
+    }
+
+    /// Return the number of addresses in the subnet, including any network and broadcast addresses.
+    /// Returns None for an IPv6 /0 Network, as 2^128 addresses does not fit in a u128.
+    pub fn number_of_addresses(&self) -> Option<u128> {
+        match self {
+            Self::V4(network) => Some(network.number_of_addresses() as u128),
+            Self::V6(network) => network.number_of_addresses(),
+        }
+    }
+
+    /// Return true if this is an IPv4 Network.
+    pub fn is_ipv4(&self) -> bool {
+        matches!(self, Self::V4(..))
+    }
+
+    /// Return true if this is an IPv6 Network.
+    pub fn is_ipv6(&self) -> bool {
+        matches!(self, Self::V6(..))
+    }
+}
+
+impl TryFrom<&str> for IpNetwork {
+    type Error = NetworkError;
+
+    /// Parse an IPv6 Network if the str contains a colon, otherwise parse an IPv4 Network.
+    fn try_from(s: &str) -> Result<IpNetwork, NetworkError> {
+        if s.contains(':') {
+            return Ok(Self::V6(Ipv6Network::try_from(s)?));
+        }
+        Ok(Self::V4(Network::try_from(s)?))
+    }
+}
+
+impl From<Network> for IpNetwork {
+    fn from(network: Network) -> Self {
+        Self::V4(network)
+    }
+}
+
+impl From<Ipv6Network> for IpNetwork {
+    fn from(network: Ipv6Network) -> Self {
+        Self::V6(network)
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    /// Format the Network as "ip_address/prefix".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::V4(network) => network.fmt(f),
+            Self::V6(network) => network.fmt(f),
+        }
+    }
+}
+
+impl IpNetwork {
+    /// Return the Network Id.
+    pub fn network_id(&self) -> IpAddr {
+        match self {
+            Self::V4(network) => IpAddr::V4(network.network_id()),
+            Self::V6(network) => IpAddr::V6(network.network_id()),
+        }
+    }
+
+    /// Return the prefix length.
+    pub fn prefix(&self) -> Prefix {
+        match self {
+            Self::V4(network) => network.cidr().into(),
+            Self::V6(network) => network.cidr().into(),
+        }
+    }
+}
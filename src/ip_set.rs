@@ -0,0 +1,185 @@
+#![warn(missing_docs)]
+
+use std::net::Ipv4Addr;
+use std::ops::RangeInclusive;
+
+use crate::aggregate::{merge_ranges, range_to_networks};
+use crate::{Ipv4Range, Network};
+
+/// A set of IPv4 addresses built from Networks and address ranges.
+/// The addresses are stored as sorted, non-overlapping and non-adjacent inclusive ranges.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct IpSet {
+    ranges: Vec<(u32, u32)>,
+}
+
+impl IpSet {
+    /// Create and initialise a new empty IpSet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add every address in the Network to the set.
+    pub fn insert_network(&mut self, network: &Network) {
+        self.insert_u32_range(u32::from(network.network_id()), network.last_address_u32());
+    }
+
+    /// Add every address in the inclusive range to the set. Nothing is added if the range is empty.
+    pub fn insert_range(&mut self, range: RangeInclusive<Ipv4Addr>) {
+        let (first, last) = (u32::from(*range.start()), u32::from(*range.end()));
+        if first <= last {
+            self.insert_u32_range(first, last);
+        }
+    }
+
+    fn insert_u32_range(&mut self, first: u32, last: u32) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.push((first, last));
+        self.ranges = merge_ranges(ranges);
+    }
+
+    /// Return true if the address is in the set.
+    pub fn contains(&self, ip_address: Ipv4Addr) -> bool {
+        self.range_containing(u32::from(ip_address)).is_some()
+    }
+
+    /// Return true if every address of the Network is in the set.
+    pub fn contains_network(&self, network: &Network) -> bool {
+        match self.range_containing(u32::from(network.network_id())) {
+            Some((_, last)) => network.last_address_u32() <= last,
+            None => false,
+        }
+    }
+
+    fn range_containing(&self, address: u32) -> Option<(u32, u32)> {
+        // Index of the first range starting after the address, so the range before it is the only candidate.
+        let index = self.ranges.partition_point(|(first, _)| *first <= address);
+        let (first, last) = *self.ranges.get(index.checked_sub(1)?)?;
+        (first <= address && address <= last).then_some((first, last))
+    }
+
+    /// Return true if the set contains no addresses.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Return the number of addresses in the set.
+    pub fn len(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(|(first, last)| (last - first) as u64 + 1)
+            .sum()
+    }
+
+    /// Return a set of the addresses in either set.
+    pub fn union(&self, other: &IpSet) -> IpSet {
+        let mut ranges = self.ranges.clone();
+        ranges.extend_from_slice(&other.ranges);
+        IpSet {
+            ranges: merge_ranges(ranges),
+        }
+    }
+
+    /// Return a set of the addresses in both sets.
+    pub fn intersection(&self, other: &IpSet) -> IpSet {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (first_a, last_a) = self.ranges[i];
+            let (first_b, last_b) = other.ranges[j];
+            let first = first_a.max(first_b);
+            let last = last_a.min(last_b);
+            if first <= last {
+                ranges.push((first, last));
+            }
+            // Move past whichever range ends first, the other may still overlap the next range.
+            if last_a < last_b {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IpSet { ranges }
+    }
+
+    /// Return a set of the addresses in this set that are not in the other set.
+    pub fn difference(&self, other: &IpSet) -> IpSet {
+        self.intersection(&other.complement())
+    }
+
+    /// Return a set of every IPv4 address not in this set.
+    pub fn complement(&self) -> IpSet {
+        let mut ranges = Vec::with_capacity(self.ranges.len() + 1);
+        let mut next = 0_u64;
+        for (first, last) in self.ranges.iter() {
+            if next < *first as u64 {
+                ranges.push((next as u32, first - 1));
+            }
+            next = *last as u64 + 1;
+        }
+        if next <= u32::MAX as u64 {
+            ranges.push((next as u32, u32::MAX));
+        }
+        IpSet { ranges }
+    }
+
+    /// Return an iterator over the inclusive address ranges in the set, in ascending order.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<Ipv4Addr>> + '_ {
+        self.ranges
+            .iter()
+            .map(|(first, last)| Ipv4Addr::from(*first)..=Ipv4Addr::from(*last))
+    }
+
+    /// Return an iterator over the minimal sorted list of Networks covering the set.
+    pub fn networks(&self) -> impl Iterator<Item = Network> + '_ {
+        self.ranges
+            .iter()
+            .flat_map(|(first, last)| range_to_networks(*first, *last))
+    }
+}
+
+impl From<Network> for IpSet {
+    fn from(network: Network) -> Self {
+        let mut set = IpSet::new();
+        set.insert_network(&network);
+        set
+    }
+}
+
+impl From<RangeInclusive<Ipv4Addr>> for IpSet {
+    fn from(range: RangeInclusive<Ipv4Addr>) -> Self {
+        let mut set = IpSet::new();
+        set.insert_range(range);
+        set
+    }
+}
+
+impl From<Ipv4Range> for IpSet {
+    fn from(range: Ipv4Range) -> Self {
+        IpSet::from(range.first()..=range.last())
+    }
+}
+
+impl FromIterator<Network> for IpSet {
+    fn from_iter<I: IntoIterator<Item = Network>>(networks: I) -> Self {
+        let ranges = networks
+            .into_iter()
+            .map(|network| (u32::from(network.network_id()), network.last_address_u32()))
+            .collect();
+        IpSet {
+            ranges: merge_ranges(ranges),
+        }
+    }
+}
+
+impl Extend<Network> for IpSet {
+    fn extend<I: IntoIterator<Item = Network>>(&mut self, networks: I) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.extend(
+            networks
+                .into_iter()
+                .map(|network| (u32::from(network.network_id()), network.last_address_u32())),
+        );
+        self.ranges = merge_ranges(ranges);
+    }
+}
@@ -0,0 +1,177 @@
+#![warn(missing_docs)]
+
+use std::fmt;
+use std::iter::FusedIterator;
+use std::net::Ipv4Addr;
+
+use crate::aggregate::range_to_networks;
+use crate::{Network, NetworkError};
+
+/// Represents an inclusive range of IPv4 addresses, which does not need to align to Network boundaries.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Ipv4Range {
+    first: Ipv4Addr,
+    last: Ipv4Addr,
+}
+
+impl Ipv4Range {
+    /// Create and initialise a new Ipv4Range from the first and last addresses in the range.
+    /// Return an InvalidRange error if the first address is greater than the last address.
+    pub fn new(first: Ipv4Addr, last: Ipv4Addr) -> Result<Self, NetworkError> {
+        if first > last {
+            return Err(NetworkError::InvalidRange);
+        }
+        Ok(Self { first, last })
+    }
+
+    /// Return the number of addresses in the range.
+    pub fn number_of_addresses(&self) -> u64 {
+        (u32::from(self.last) - u32::from(self.first)) as u64 + 1
+    }
+
+    /// Return true if the address is inside the range.
+    pub fn contains(&self, ip_address: Ipv4Addr) -> bool {
+        self.first <= ip_address && ip_address <= self.last
+    }
+
+    /// Return the minimal sorted list of Networks covering exactly the addresses in the range.
+    pub fn to_networks(&self) -> Vec<Network> {
+        range_to_networks(u32::from(self.first), u32::from(self.last))
+    }
+
+    /// Return an iterator over every address in the range.
+    pub fn iter(&self) -> Addresses {
+        Addresses::new(u32::from(self.first), u32::from(self.last))
+    }
+}
+
+impl Ipv4Range {
+    /// Return the first address in the range.
+    pub fn first(&self) -> Ipv4Addr {
+        self.first
+    }
+
+    /// Return the last address in the range.
+    pub fn last(&self) -> Ipv4Addr {
+        self.last
+    }
+}
+
+impl From<Network> for Ipv4Range {
+    /// Return the range of every address in the Network, including the network and broadcast addresses.
+    fn from(network: Network) -> Self {
+        Self {
+            first: network.network_id(),
+            last: Ipv4Addr::from(network.last_address_u32()),
+        }
+    }
+}
+
+impl TryFrom<&str> for Ipv4Range {
+    type Error = NetworkError;
+
+    /// Parse a str in the format "first_address-last_address".
+    fn try_from(s: &str) -> Result<Ipv4Range, NetworkError> {
+        let (first, last) = s.trim().split_once('-').ok_or(NetworkError::ParsingError)?;
+        let first = Network::try_str_to_ipv4addr(first.trim())?;
+        let last = Network::try_str_to_ipv4addr(last.trim())?;
+        Ipv4Range::new(first, last)
+    }
+}
+
+impl fmt::Display for Ipv4Range {
+    /// Format the range as "first_address-last_address".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.first, self.last)
+    }
+}
+
+impl IntoIterator for Ipv4Range {
+    type Item = Ipv4Addr;
+    type IntoIter = Addresses;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over a run of consecutive IPv4 addresses, returned by Ipv4Range::iter, Network::hosts and Network::addresses.
+/// It can be iterated from either end, and skipping ahead with nth is O(1).
+/// On 64 bit targets it is also an ExactSizeIterator; number_of_addresses gives the exact count on every target.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Addresses {
+    // The remaining addresses are front..back, kept as u64 so that a range ending at 255.255.255.255 can be represented.
+    front: u64,
+    back: u64,
+}
+
+impl Addresses {
+    /// Create an iterator from first to last inclusive.
+    pub(crate) fn new(first: u32, last: u32) -> Self {
+        Self {
+            front: first as u64,
+            back: last as u64 + 1,
+        }
+    }
+
+    /// Create an iterator over no addresses.
+    pub(crate) fn empty() -> Self {
+        Self { front: 0, back: 0 }
+    }
+}
+
+impl Iterator for Addresses {
+    type Item = Ipv4Addr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.nth(0)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if (n as u64) >= self.back - self.front {
+            self.front = self.back;
+            return None;
+        }
+        let address = self.front + n as u64;
+        self.front = address + 1;
+        Some(Ipv4Addr::from(address as u32))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        match usize::try_from(len) {
+            Ok(len) => (len, Some(len)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+
+    fn count(self) -> usize {
+        usize::try_from(self.back - self.front).unwrap_or(usize::MAX)
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for Addresses {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.nth_back(0)
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if (n as u64) >= self.back - self.front {
+            self.back = self.front;
+            return None;
+        }
+        let address = self.back - 1 - n as u64;
+        self.back = address;
+        Some(Ipv4Addr::from(address as u32))
+    }
+}
+
+/// Only implemented where a usize can hold the 2^32 addresses of 0.0.0.0/0.
+#[cfg(target_pointer_width = "64")]
+impl ExactSizeIterator for Addresses {}
+
+impl FusedIterator for Addresses {}
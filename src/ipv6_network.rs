@@ -0,0 +1,81 @@
+#![warn(missing_docs)]
+
+use std::fmt;
+use std::net::Ipv6Addr;
+
+use crate::Ipv6Cidr;
+use crate::NetworkError;
+
+/// Represents a IPv6 Network by storing it's Network address and CIDR value.
+/// IPv6 has no broadcast address, so every address in the Network is usable.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Ipv6Network {
+    network_id: Ipv6Addr,
+    cidr: Ipv6Cidr,
+}
+
+impl Ipv6Network {
+    /// Create and initialise a new Ipv6Network struct. Takes a std::net::Ipv6Addr and an Ipv6Cidr.  Convert Ipv6Addr into the network address before storing and returning the struct.
+    pub fn new(ip_address: Ipv6Addr, cidr: Ipv6Cidr) -> Result<Self, NetworkError> {
+        let bitmask = cidr.to_bitmask();
+        let id = u128::from(ip_address) & bitmask;
+        Ok(Self {
+            network_id: Ipv6Addr::from(id),
+            cidr,
+        })
+    }
+
+    /// Return the first address in the subnet
+    pub fn first_address(&self) -> Ipv6Addr {
+        self.network_id
+    }
+
+    /// Return the last address in the subnet
+    pub fn last_address(&self) -> Ipv6Addr {
+        let bitmask = !self.cidr.to_bitmask();
+        Ipv6Addr::from(u128::from(self.network_id) | bitmask)
+    }
+
+    /// Return the number of addresses in the subnet.
+    /// Returns None for a /0 Network, as 2^128 addresses does not fit in a u128.
+    pub fn number_of_addresses(&self) -> Option<u128> {
+        1_u128.checked_shl(128 - *self.cidr as u32)
+    }
+}
+
+impl TryFrom<&str> for Ipv6Network {
+    type Error = NetworkError;
+
+    fn try_from(s: &str) -> Result<Ipv6Network, NetworkError> {
+        let s = s.trim();
+
+        if let Some(pos) = s.find('/') {
+            let ip_addr = s[0..pos]
+                .parse::<Ipv6Addr>()
+                .map_err(|_| NetworkError::IPv6AddressError)?;
+            if let Ok(num) = s[(pos + 1)..].parse::<u8>() {
+                return Ipv6Network::new(ip_addr, Ipv6Cidr::new(num)?);
+            }
+        }
+        Err(NetworkError::ParsingError)
+    }
+}
+
+impl fmt::Display for Ipv6Network {
+    /// Format the Network as "ip_address/cidr".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network_id, *self.cidr)
+    }
+}
+
+impl Ipv6Network {
+    /// Return the Network Id.
+    pub fn network_id(&self) -> Ipv6Addr {
+        self.network_id
+    }
+
+    /// Return the CIDR value.
+    pub fn cidr(&self) -> Ipv6Cidr {
+        self.cidr
+    }
+}
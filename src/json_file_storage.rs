@@ -0,0 +1,103 @@
+#![warn(missing_docs)]
+
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::ledger::{LedgerError, Storage};
+use crate::Network;
+
+/// Storage that keeps the allocations of every base network in a JSON file, as an object mapping each
+/// base network to the list of networks allocated from it.
+/// Updates hold an exclusive lock on a "<path>.lock" file next to the JSON file, and replace the JSON file
+/// atomically by renaming a fully written temporary file over it, so concurrent processes never lose updates
+/// or read a partly written file.
+#[derive(Debug, Clone)]
+pub struct JsonFileStorage {
+    path: PathBuf,
+}
+
+impl JsonFileStorage {
+    /// Create and initialise a new JsonFileStorage using the file at path, which is created on the first update.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Return the path of the JSON file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn sibling(&self, extension: &str) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(extension);
+        PathBuf::from(path)
+    }
+
+    #[cfg(unix)]
+    fn directory(&self) -> &Path {
+        match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        }
+    }
+
+    fn open_lock_file(&self) -> Result<File, LedgerError> {
+        Ok(OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.sibling(".lock"))?)
+    }
+
+    fn read(&self) -> Result<BTreeMap<Network, Vec<Network>>, LedgerError> {
+        match fs::read(&self.path) {
+            Ok(contents) => Ok(serde_json::from_slice(&contents)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn write(&self, allocations: &BTreeMap<Network, Vec<Network>>) -> Result<(), LedgerError> {
+        let temporary_path = self.sibling(".tmp");
+        let file = File::create(&temporary_path)?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, allocations)?;
+        writer.write_all(b"\n")?;
+        let file = writer.into_inner().map_err(|err| err.into_error())?;
+        file.sync_all()?;
+        fs::rename(&temporary_path, &self.path)?;
+        // Sync the directory too, so the rename itself is not lost in a crash.
+        // Directories cannot be opened as files on Windows, where the rename is already durable.
+        #[cfg(unix)]
+        File::open(self.directory())?.sync_all()?;
+        Ok(())
+    }
+}
+
+impl Storage for JsonFileStorage {
+    fn load(&self, base_network: &Network) -> Result<Vec<Network>, LedgerError> {
+        let lock = self.open_lock_file()?;
+        lock.lock_shared()?;
+        Ok(self.read()?.remove(base_network).unwrap_or_default())
+    }
+
+    fn update<T, F>(&mut self, base_network: &Network, f: F) -> Result<T, LedgerError>
+    where
+        F: FnOnce(&mut Vec<Network>) -> Result<T, LedgerError>,
+    {
+        // The lock is released when the file is closed at the end of the update.
+        let lock = self.open_lock_file()?;
+        lock.lock()?;
+
+        let mut allocations = self.read()?;
+        let mut networks = allocations.remove(base_network).unwrap_or_default();
+        let result = f(&mut networks)?;
+        if !networks.is_empty() {
+            allocations.insert(base_network.clone(), networks);
+        }
+        self.write(&allocations)?;
+        Ok(result)
+    }
+}
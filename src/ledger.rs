@@ -0,0 +1,258 @@
+#![warn(missing_docs)]
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+
+use crate::subnet::plan;
+use crate::{Cidr, FirstFit, Network, NetworkError, Placement, Pool, Slsm, Subnetting, Vlsm};
+
+/// LedgerError enumerates the errors returned by a Ledger and its storage backends.
+#[derive(Debug)]
+pub enum LedgerError {
+    /// The allocation was refused
+    Network(NetworkError),
+    /// The storage could not be read or written
+    Io(io::Error),
+    /// The JSON file could not be parsed or written
+    Json(serde_json::Error),
+    /// The SQLite database could not be read or written
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error),
+    /// A stored allocation is not a valid network
+    InvalidRecord(String),
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network(err) => write!(f, "{err}"),
+            Self::Io(err) => write!(f, "ledger storage error: {err}"),
+            Self::Json(err) => write!(f, "ledger file error: {err}"),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(err) => write!(f, "ledger database error: {err}"),
+            Self::InvalidRecord(record) => {
+                write!(f, "stored allocation \"{record}\" is not a valid network")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LedgerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Network(err) => Some(err),
+            Self::Io(err) => Some(err),
+            Self::Json(err) => Some(err),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(err) => Some(err),
+            Self::InvalidRecord(_) => None,
+        }
+    }
+}
+
+impl From<NetworkError> for LedgerError {
+    fn from(err: NetworkError) -> Self {
+        Self::Network(err)
+    }
+}
+
+impl From<io::Error> for LedgerError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for LedgerError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for LedgerError {
+    fn from(err: rusqlite::Error) -> Self {
+        Self::Sqlite(err)
+    }
+}
+
+/// Stores the networks allocated from each base network for a Ledger.
+pub trait Storage {
+    /// Return the networks recorded as allocated from the base network.
+    fn load(&self, base_network: &Network) -> Result<Vec<Network>, LedgerError>;
+
+    /// Run f on the networks recorded as allocated from the base network, and store the networks it leaves behind
+    /// if it succeeds. Other processes using the same storage must not be able to change the allocations in between.
+    fn update<T, F>(&mut self, base_network: &Network, f: F) -> Result<T, LedgerError>
+    where
+        F: FnOnce(&mut Vec<Network>) -> Result<T, LedgerError>;
+}
+
+/// Storage that keeps allocations in memory, for the lifetime of the Ledger.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryStorage {
+    allocations: BTreeMap<Network, Vec<Network>>,
+}
+
+impl MemoryStorage {
+    /// Create and initialise a new empty MemoryStorage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load(&self, base_network: &Network) -> Result<Vec<Network>, LedgerError> {
+        Ok(self
+            .allocations
+            .get(base_network)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn update<T, F>(&mut self, base_network: &Network, f: F) -> Result<T, LedgerError>
+    where
+        F: FnOnce(&mut Vec<Network>) -> Result<T, LedgerError>,
+    {
+        let mut networks = self.load(base_network)?;
+        let result = f(&mut networks)?;
+        if networks.is_empty() {
+            self.allocations.remove(base_network);
+        } else {
+            self.allocations.insert(base_network.clone(), networks);
+        }
+        Ok(result)
+    }
+}
+
+/// Records the networks allocated from base networks in a Storage, so that later allocations, including those made
+/// by other runs or processes sharing the storage, skip networks that are already in use.
+/// Every allocation is made by loading the recorded networks into a Pool, and nothing is stored if it fails.
+#[derive(Debug, Clone)]
+pub struct Ledger<S: Storage> {
+    storage: S,
+}
+
+impl<S: Storage> Ledger<S> {
+    /// Create and initialise a new Ledger backed by the storage.
+    pub fn new(storage: S) -> Self {
+        Self { storage }
+    }
+
+    /// Return the storage backing the Ledger.
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Return the networks allocated from the base network, ordered by Network.
+    pub fn allocated(&self, base_network: &Network) -> Result<Vec<Network>, LedgerError> {
+        Ok(self.pool(base_network)?.allocated().cloned().collect())
+    }
+
+    /// Return a Pool holding the current allocations from the base network.
+    /// Changes to the Pool are not recorded in the Ledger.
+    pub fn pool(&self, base_network: &Network) -> Result<Pool, LedgerError> {
+        load_pool(base_network, &self.storage.load(base_network)?)
+    }
+
+    /// Allocate, record and return a free subnet of the base network with the given CIDR, as with Pool::allocate.
+    pub fn allocate(&mut self, base_network: &Network, cidr: Cidr) -> Result<Network, LedgerError> {
+        self.with_pool(base_network, |pool| pool.allocate(cidr))
+    }
+
+    /// Allocate, record and return the smallest free subnet of the base network with room for the hosts,
+    /// as with Pool::allocate_hosts.
+    pub fn allocate_hosts(
+        &mut self,
+        base_network: &Network,
+        hosts: u32,
+    ) -> Result<Network, LedgerError> {
+        self.with_pool(base_network, |pool| pool.allocate_hosts(hosts))
+    }
+
+    /// Allocate and record exactly the given network, as with Pool::allocate_specific.
+    pub fn allocate_specific(
+        &mut self,
+        base_network: &Network,
+        network: Network,
+    ) -> Result<(), LedgerError> {
+        self.with_pool(base_network, |pool| pool.allocate_specific(network))
+    }
+
+    /// Release a recorded network, as with Pool::release.
+    pub fn release(
+        &mut self,
+        base_network: &Network,
+        network: &Network,
+    ) -> Result<(), LedgerError> {
+        self.with_pool(base_network, |pool| pool.release(network))
+    }
+
+    /// Allocate and record the first count subnets of the Slsm that are not already in use.
+    /// Return a PoolExhausted error, and record nothing, if fewer than count subnets are free.
+    pub fn allocate_slsm(
+        &mut self,
+        slsm: &Slsm,
+        count: usize,
+    ) -> Result<Vec<Network>, LedgerError> {
+        self.with_pool(slsm.base_network(), |pool| {
+            let subnets: Vec<Network> = slsm
+                .subnets()
+                .filter(|subnet| pool.free().contains_network(subnet))
+                .take(count)
+                .collect();
+            if subnets.len() < count {
+                return Err(NetworkError::PoolExhausted { cidr: *slsm.cidr() });
+            }
+            for subnet in subnets.iter() {
+                pool.allocate_specific(subnet.clone())?;
+            }
+            Ok(subnets)
+        })
+    }
+
+    /// Allocate and record a subnet for each of the Vlsm's requirements in the space that is not already in use,
+    /// in the same order as the Vlsm and placed first-fit. On an empty base network this gives the same subnets
+    /// as iterating over a Vlsm created with first-fit placement.
+    /// Reserved buddy blocks are recorded as allocations too, so they stay free for their subnet until released.
+    /// Return an InsufficientAddressSpace, FragmentedAddressSpace or AllocationConflict error, and record nothing,
+    /// if the subnets do not all fit.
+    pub fn allocate_vlsm(&mut self, vlsm: &Vlsm) -> Result<Vec<Network>, LedgerError> {
+        self.allocate_vlsm_with_placement(vlsm, &FirstFit)
+    }
+
+    /// Allocate and record a subnet for each of the Vlsm's requirements like allocate_vlsm,
+    /// using the placement strategy to choose where in the free space each subnet is allocated.
+    pub fn allocate_vlsm_with_placement(
+        &mut self,
+        vlsm: &Vlsm,
+        placement: &dyn Placement,
+    ) -> Result<Vec<Network>, LedgerError> {
+        self.with_pool(vlsm.base_network(), |pool| {
+            Ok(plan(pool, vlsm.requirements(), placement)?
+                .into_iter()
+                .map(|allocation| allocation.network().clone())
+                .collect())
+        })
+    }
+
+    fn with_pool<T, F>(&mut self, base_network: &Network, f: F) -> Result<T, LedgerError>
+    where
+        F: FnOnce(&mut Pool) -> Result<T, NetworkError>,
+    {
+        self.storage.update(base_network, |networks| {
+            let mut pool = load_pool(base_network, networks)?;
+            let result = f(&mut pool)?;
+            *networks = pool.allocated().cloned().collect();
+            Ok(result)
+        })
+    }
+}
+
+fn load_pool(base_network: &Network, networks: &[Network]) -> Result<Pool, LedgerError> {
+    let mut pool = Pool::new(base_network.clone());
+    for network in networks {
+        pool.allocate_specific(network.clone())?;
+    }
+    Ok(pool)
+}
@@ -1,21 +1,74 @@
-pub use crate::cidr::Cidr;
-pub use crate::network::Network;
+pub use crate::aggregate::collapse_networks;
+pub use crate::cidr::{Cidr, Ipv6Cidr};
+pub use crate::ip_network::IpNetwork;
+pub use crate::ip_set::IpSet;
+pub use crate::ipv4_range::{Addresses, Ipv4Range};
+pub use crate::ipv6_network::Ipv6Network;
+#[cfg(feature = "ledger")]
+pub use crate::json_file_storage::JsonFileStorage;
+#[cfg(feature = "ledger")]
+pub use crate::ledger::{Ledger, LedgerError, MemoryStorage, Storage};
+pub use crate::network::{Network, Relation};
 pub use crate::network_error::NetworkError;
-pub use crate::subnet::{Slsm, Vlsm};
-
+pub use crate::placement::{BestFit, FirstFit, Placement, TopDown};
+pub use crate::pool::Pool;
+pub use crate::prefix::Prefix;
+pub use crate::prefix_map::PrefixMap;
+pub use crate::requirement::{Allocation, Growth, Requirement, RequirementKind};
+pub use crate::special_purpose::{AddressClass, Classification, ClassifyAddress};
+#[cfg(feature = "sqlite")]
+pub use crate::sqlite_storage::SqliteStorage;
+pub use crate::subnet::{IpSlsm, IpVlsm, Ipv6Slsm, Ipv6Vlsm, Slsm, Vlsm};
+pub use crate::subnetting::Subnetting;
+pub use crate::vlsm_report::VlsmReport;
+
+mod aggregate;
 mod cidr;
+mod ip_network;
+mod ip_set;
+mod ipv4_range;
+mod ipv6_network;
+#[cfg(feature = "ledger")]
+mod json_file_storage;
+#[cfg(feature = "ledger")]
+mod ledger;
 mod network;
 mod network_error;
+mod placement;
+mod pool;
+mod prefix;
+mod prefix_map;
+mod requirement;
+#[cfg(feature = "serde")]
+mod serialize;
+mod special_purpose;
+#[cfg(feature = "sqlite")]
+mod sqlite_storage;
 mod subnet;
+mod subnetting;
+mod vlsm_report;
 
 #[cfg(test)]
 mod tests {
-    use std::net::Ipv4Addr;
-
-    use crate::cidr::Cidr;
-    use crate::network::Network;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::num::NonZeroU32;
+
+    use crate::aggregate::collapse_networks;
+    use crate::cidr::{Cidr, Ipv6Cidr};
+    use crate::ip_network::IpNetwork;
+    use crate::ip_set::IpSet;
+    use crate::ipv4_range::Ipv4Range;
+    use crate::ipv6_network::Ipv6Network;
+    use crate::network::{Network, Relation};
     use crate::network_error::NetworkError;
-    use crate::subnet::{Slsm, Vlsm};
+    use crate::placement::{BestFit, FirstFit, Placement, TopDown};
+    use crate::pool::Pool;
+    use crate::prefix::Prefix;
+    use crate::prefix_map::PrefixMap;
+    use crate::requirement::{Growth, Requirement, RequirementKind};
+    use crate::special_purpose::{AddressClass, ClassifyAddress};
+    use crate::subnet::{IpSlsm, IpVlsm, Ipv6Slsm, Ipv6Vlsm, Slsm, Vlsm};
+    use crate::subnetting::Subnetting;
 
     #[test]
     fn create_network() {
@@ -809,9 +862,9 @@ mod tests {
             Network::new(Ipv4Addr::new(172, 16, 16, 0), Cidr::new(21).unwrap()).unwrap(),
             Network::new(Ipv4Addr::new(172, 16, 24, 0), Cidr::new(25).unwrap()).unwrap(),
             Network::new(Ipv4Addr::new(172, 16, 24, 128), Cidr::new(25).unwrap()).unwrap(),
-            Network::new(Ipv4Addr::new(172, 16, 25, 0), Cidr::new(30).unwrap()).unwrap(),
-            Network::new(Ipv4Addr::new(172, 16, 25, 4), Cidr::new(30).unwrap()).unwrap(),
-            Network::new(Ipv4Addr::new(172, 16, 25, 8), Cidr::new(30).unwrap()).unwrap(),
+            Network::new(Ipv4Addr::new(172, 16, 25, 0), Cidr::new(31).unwrap()).unwrap(),
+            Network::new(Ipv4Addr::new(172, 16, 25, 2), Cidr::new(31).unwrap()).unwrap(),
+            Network::new(Ipv4Addr::new(172, 16, 25, 4), Cidr::new(31).unwrap()).unwrap(),
         ];
         let got_subnets: Vec<Network> = subnet.collect();
         assert_eq!(got_base_network_id, want_base_network_id);
@@ -821,13 +874,16 @@ mod tests {
         let cidr = Cidr::new(26).unwrap();
         let network = Network::new(ip_address, cidr).unwrap();
         let required_hosts = vec![1000, 200];
-        let subnet = Vlsm::new(network, required_hosts).unwrap();
-        let got_base_network_id = subnet.base_network().network_id();
-        let want_base_network_id = Ipv4Addr::new(152, 18, 84, 192);
-        let want_subnets = vec![];
-        let got_subnets: Vec<Network> = subnet.collect();
-        assert_eq!(got_base_network_id, want_base_network_id);
-        assert_eq!(got_subnets, want_subnets);
+        let subnet = Vlsm::new(network, required_hosts);
+        assert_eq!(
+            subnet.unwrap_err(),
+            NetworkError::InsufficientAddressSpace {
+                index: 0,
+                hosts: 1000,
+                required: 1280,
+                available: 64,
+            }
+        );
 
         let ip_address = Ipv4Addr::new(1, 1, 1, 91);
         let cidr = Cidr::new(29).unwrap();
@@ -837,8 +893,8 @@ mod tests {
         let got_base_network_id = subnet.base_network().network_id();
         let want_base_network_id = Ipv4Addr::new(1, 1, 1, 88);
         let want_subnets = vec![
-            Network::new(Ipv4Addr::new(1, 1, 1, 88), Cidr::new(30).unwrap()).unwrap(),
-            Network::new(Ipv4Addr::new(1, 1, 1, 92), Cidr::new(30).unwrap()).unwrap(),
+            Network::new(Ipv4Addr::new(1, 1, 1, 88), Cidr::new(31).unwrap()).unwrap(),
+            Network::new(Ipv4Addr::new(1, 1, 1, 90), Cidr::new(31).unwrap()).unwrap(),
         ];
         let got_subnets: Vec<Network> = subnet.collect();
         assert_eq!(got_base_network_id, want_base_network_id);
@@ -847,29 +903,2753 @@ mod tests {
         let ip_address = Ipv4Addr::new(1, 1, 1, 91);
         let cidr = Cidr::new(29).unwrap();
         let network = Network::new(ip_address, cidr).unwrap();
-        let required_hosts = vec![2, 2, 2];
-        let subnet = Vlsm::new(network, required_hosts).unwrap();
-        let got_base_network_id = subnet.base_network().network_id();
-        let want_base_network_id = Ipv4Addr::new(1, 1, 1, 88);
-        let want_subnets = vec![
-            Network::new(Ipv4Addr::new(1, 1, 1, 88), Cidr::new(30).unwrap()).unwrap(),
-            Network::new(Ipv4Addr::new(1, 1, 1, 92), Cidr::new(30).unwrap()).unwrap(),
-        ];
-        let got_subnets: Vec<Network> = subnet.collect();
-        assert_eq!(got_base_network_id, want_base_network_id);
-        assert_eq!(got_subnets, want_subnets);
+        let required_hosts = vec![2, 2, 2, 2, 2];
+        let subnet = Vlsm::new(network, required_hosts);
+        assert_eq!(
+            subnet.unwrap_err(),
+            NetworkError::InsufficientAddressSpace {
+                index: 4,
+                hosts: 2,
+                required: 10,
+                available: 8,
+            }
+        );
 
         let ip_address = Ipv4Addr::new(1, 1, 1, 91);
         let cidr = Cidr::new(29).unwrap();
         let network = Network::new(ip_address, cidr).unwrap();
-        let required_hosts = vec![4, 2];
+        let required_hosts = vec![2, 4];
+        let subnet = Vlsm::new(network, required_hosts);
+        assert_eq!(
+            subnet.unwrap_err(),
+            NetworkError::InsufficientAddressSpace {
+                index: 0,
+                hosts: 2,
+                required: 10,
+                available: 8,
+            }
+        );
+
+        let ip_address = Ipv4Addr::new(1, 1, 1, 91);
+        let cidr = Cidr::new(29).unwrap();
+        let network = Network::new(ip_address, cidr).unwrap();
+        let required_hosts = vec![6];
         let subnet = Vlsm::new(network, required_hosts).unwrap();
-        let got_base_network_id = subnet.base_network().network_id();
-        let want_base_network_id = Ipv4Addr::new(1, 1, 1, 88);
         let want_subnets =
             vec![Network::new(Ipv4Addr::new(1, 1, 1, 88), Cidr::new(29).unwrap()).unwrap()];
         let got_subnets: Vec<Network> = subnet.collect();
-        assert_eq!(got_base_network_id, want_base_network_id);
         assert_eq!(got_subnets, want_subnets);
+
+        let ip_address = Ipv4Addr::new(10, 0, 0, 0);
+        let cidr = Cidr::new(24).unwrap();
+        let network = Network::new(ip_address, cidr).unwrap();
+        let required_hosts = vec![20, 100, 60, 31];
+        let subnet = Vlsm::new(network, required_hosts);
+        assert_eq!(
+            subnet.unwrap_err(),
+            NetworkError::InsufficientAddressSpace {
+                index: 0,
+                hosts: 20,
+                required: 288,
+                available: 256,
+            }
+        );
+    }
+
+    #[test]
+    fn create_ipv6_network() {
+        let ip_address = Ipv6Addr::new(0x2001, 0xdb8, 0x85a3, 0x1234, 0, 0x8a2e, 0x370, 0x7334);
+        let cidr = Ipv6Cidr::new(0).unwrap();
+        let network = Ipv6Network::new(ip_address, cidr).unwrap();
+        assert_eq!(network.network_id(), Ipv6Addr::UNSPECIFIED);
+        assert_eq!(network.cidr(), cidr);
+
+        let cidr = Ipv6Cidr::new(48).unwrap();
+        let network = Ipv6Network::new(ip_address, cidr).unwrap();
+        assert_eq!(
+            network.network_id(),
+            Ipv6Addr::new(0x2001, 0xdb8, 0x85a3, 0, 0, 0, 0, 0)
+        );
+
+        let cidr = Ipv6Cidr::new(63).unwrap();
+        let network = Ipv6Network::new(ip_address, cidr).unwrap();
+        assert_eq!(
+            network.network_id(),
+            Ipv6Addr::new(0x2001, 0xdb8, 0x85a3, 0x1234, 0, 0, 0, 0)
+        );
+
+        let cidr = Ipv6Cidr::new(128).unwrap();
+        let network = Ipv6Network::new(ip_address, cidr).unwrap();
+        assert_eq!(network.network_id(), ip_address);
+
+        let cidr = Ipv6Cidr::new(129);
+        assert_eq!(cidr, Err(NetworkError::CidrOutOfRangeError));
+
+        let cidr = Ipv6Cidr::new(255);
+        assert_eq!(cidr, Err(NetworkError::CidrOutOfRangeError));
+    }
+
+    #[test]
+    fn get_ipv6_first_and_last_address() {
+        let network = Ipv6Network::try_from("::/0").unwrap();
+        assert_eq!(network.first_address(), Ipv6Addr::UNSPECIFIED);
+        assert_eq!(network.last_address(), Ipv6Addr::from(u128::MAX));
+        assert_eq!(network.number_of_addresses(), None);
+
+        let network = Ipv6Network::try_from("2001:db8::1/32").unwrap();
+        assert_eq!(
+            network.first_address(),
+            "2001:db8::".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_eq!(
+            network.last_address(),
+            "2001:db8:ffff:ffff:ffff:ffff:ffff:ffff"
+                .parse::<Ipv6Addr>()
+                .unwrap()
+        );
+        assert_eq!(network.number_of_addresses(), Some(1 << 96));
+
+        let network = Ipv6Network::try_from("fe80::1:2:3:4/64").unwrap();
+        assert_eq!(
+            network.first_address(),
+            "fe80::".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_eq!(
+            network.last_address(),
+            "fe80::ffff:ffff:ffff:ffff".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_eq!(network.number_of_addresses(), Some(1 << 64));
+
+        let network = Ipv6Network::try_from("2001:db8::4/127").unwrap();
+        assert_eq!(
+            network.first_address(),
+            "2001:db8::4".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_eq!(
+            network.last_address(),
+            "2001:db8::5".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_eq!(network.number_of_addresses(), Some(2));
+
+        let network = Ipv6Network::try_from("2001:db8::5/128").unwrap();
+        assert_eq!(
+            network.first_address(),
+            "2001:db8::5".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_eq!(
+            network.last_address(),
+            "2001:db8::5".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_eq!(network.number_of_addresses(), Some(1));
+    }
+
+    #[test]
+    fn create_ipv6_network_from_str() {
+        let network = Ipv6Network::try_from(" 2001:db8:abcd:12::1/56 ");
+        assert_eq!(
+            network,
+            Ipv6Network::new(
+                Ipv6Addr::new(0x2001, 0xdb8, 0xabcd, 0, 0, 0, 0, 0),
+                Ipv6Cidr::new(56).unwrap()
+            )
+        );
+
+        let network = Ipv6Network::try_from("::ffff:10.1.2.3/120");
+        assert_eq!(
+            network,
+            Ipv6Network::new(
+                Ipv6Addr::new(0, 0, 0, 0, 0, 0xffff, 0x0a01, 0x0200),
+                Ipv6Cidr::new(120).unwrap()
+            )
+        );
+
+        let network = Ipv6Network::try_from("");
+        assert_eq!(network, Err(NetworkError::ParsingError));
+
+        let network = Ipv6Network::try_from("2001:db8::");
+        assert_eq!(network, Err(NetworkError::ParsingError));
+
+        let network = Ipv6Network::try_from("2001:db8::/");
+        assert_eq!(network, Err(NetworkError::ParsingError));
+
+        let network = Ipv6Network::try_from("2001:db8::/1x");
+        assert_eq!(network, Err(NetworkError::ParsingError));
+
+        let network = Ipv6Network::try_from("2001:db8::/129");
+        assert_eq!(network, Err(NetworkError::CidrOutOfRangeError));
+
+        let network = Ipv6Network::try_from("2001:db8:::1/64");
+        assert_eq!(network, Err(NetworkError::IPv6AddressError));
+
+        let network = Ipv6Network::try_from("10.1.2.3/24");
+        assert_eq!(network, Err(NetworkError::IPv6AddressError));
+
+        let network = Ipv6Network::try_from("/64");
+        assert_eq!(network, Err(NetworkError::IPv6AddressError));
+    }
+
+    #[test]
+    fn create_ipv6_slsm_subnet() {
+        let network = Ipv6Network::try_from("2001:db8:1234::/48").unwrap();
+        let subnet_cidr = Ipv6Cidr::new(50).unwrap();
+        let subnet = Ipv6Slsm::new(network.clone(), subnet_cidr).unwrap();
+        assert_eq!(subnet.base_network(), &network);
+        assert_eq!(subnet.cidr(), subnet_cidr);
+        let want_subnets = vec![
+            Ipv6Network::try_from("2001:db8:1234::/50").unwrap(),
+            Ipv6Network::try_from("2001:db8:1234:4000::/50").unwrap(),
+            Ipv6Network::try_from("2001:db8:1234:8000::/50").unwrap(),
+            Ipv6Network::try_from("2001:db8:1234:c000::/50").unwrap(),
+        ];
+        let got_subnets: Vec<Ipv6Network> = subnet.collect();
+        assert_eq!(got_subnets, want_subnets);
+
+        let network = Ipv6Network::try_from("2001:db8::/127").unwrap();
+        let subnet = Ipv6Slsm::new(network, Ipv6Cidr::new(128).unwrap()).unwrap();
+        let want_subnets = vec![
+            Ipv6Network::try_from("2001:db8::/128").unwrap(),
+            Ipv6Network::try_from("2001:db8::1/128").unwrap(),
+        ];
+        let got_subnets: Vec<Ipv6Network> = subnet.collect();
+        assert_eq!(got_subnets, want_subnets);
+
+        let network = Ipv6Network::try_from("2001:db8::/64").unwrap();
+        let subnet = Ipv6Slsm::new(network.clone(), Ipv6Cidr::new(64).unwrap()).unwrap();
+        let got_subnets: Vec<Ipv6Network> = subnet.collect();
+        assert_eq!(got_subnets, vec![network]);
+
+        let network = Ipv6Network::try_from("::/0").unwrap();
+        let mut subnet = Ipv6Slsm::new(network, Ipv6Cidr::new(128).unwrap()).unwrap();
+        assert_eq!(
+            subnet.next(),
+            Some(Ipv6Network::try_from("::/128").unwrap())
+        );
+        assert_eq!(
+            subnet.next(),
+            Some(Ipv6Network::try_from("::1/128").unwrap())
+        );
+
+        let network = Ipv6Network::try_from("::/0").unwrap();
+        let subnet = Ipv6Slsm::new(network, Ipv6Cidr::new(1).unwrap()).unwrap();
+        let want_subnets = vec![
+            Ipv6Network::try_from("::/1").unwrap(),
+            Ipv6Network::try_from("8000::/1").unwrap(),
+        ];
+        let got_subnets: Vec<Ipv6Network> = subnet.collect();
+        assert_eq!(got_subnets, want_subnets);
+
+        let network = Ipv6Network::try_from("2001:db8::/64").unwrap();
+        let subnet = Ipv6Slsm::new(network, Ipv6Cidr::new(48).unwrap());
+        assert_eq!(subnet.unwrap_err(), NetworkError::InvalidSubnetCidr);
+    }
+
+    #[test]
+    fn create_ip_network() {
+        let prefix = Prefix::new(20).unwrap();
+        let network = IpNetwork::new(IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3)), prefix).unwrap();
+        assert_eq!(
+            network,
+            IpNetwork::V4(
+                Network::new(Ipv4Addr::new(10, 1, 0, 0), Cidr::new(20).unwrap()).unwrap()
+            )
+        );
+        assert_eq!(network.network_id(), IpAddr::V4(Ipv4Addr::new(10, 1, 0, 0)));
+        assert_eq!(network.prefix(), prefix);
+        assert_eq!(
+            network.first_address(),
+            IpAddr::V4(Ipv4Addr::new(10, 1, 0, 0))
+        );
+        assert_eq!(
+            network.last_address(),
+            IpAddr::V4(Ipv4Addr::new(10, 1, 15, 255))
+        );
+        assert_eq!(network.number_of_addresses(), Some(4096));
+        assert!(network.is_ipv4());
+        assert!(!network.is_ipv6());
+
+        let ip_address: IpAddr = "2001:db8::1".parse().unwrap();
+        let network = IpNetwork::new(ip_address, Prefix::new(64).unwrap()).unwrap();
+        assert_eq!(
+            network,
+            IpNetwork::V6(Ipv6Network::try_from("2001:db8::/64").unwrap())
+        );
+        assert_eq!(network.prefix(), Prefix::new(64).unwrap());
+        assert_eq!(
+            network.last_address(),
+            "2001:db8::ffff:ffff:ffff:ffff".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(network.number_of_addresses(), Some(1 << 64));
+        assert!(network.is_ipv6());
+
+        let network = IpNetwork::new(
+            IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3)),
+            Prefix::new(32).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            network.last_address(),
+            IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3))
+        );
+        assert_eq!(network.number_of_addresses(), Some(1));
+
+        let network = IpNetwork::new(
+            IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3)),
+            Prefix::new(33).unwrap(),
+        );
+        assert_eq!(network, Err(NetworkError::CidrOutOfRangeError));
+
+        let network = IpNetwork::new(ip_address, Prefix::new(128).unwrap());
+        assert!(network.is_ok());
+
+        let prefix = Prefix::new(129);
+        assert_eq!(prefix, Err(NetworkError::CidrOutOfRangeError));
+
+        assert_eq!(
+            Prefix::from(Cidr::new(24).unwrap()),
+            Prefix::new(24).unwrap()
+        );
+        assert_eq!(
+            Prefix::from(Ipv6Cidr::new(96).unwrap()),
+            Prefix::new(96).unwrap()
+        );
+        assert_eq!(Cidr::try_from(Prefix::new(24).unwrap()), Cidr::new(24));
+        assert_eq!(
+            Cidr::try_from(Prefix::new(96).unwrap()),
+            Err(NetworkError::CidrOutOfRangeError)
+        );
+        assert_eq!(
+            Ipv6Cidr::try_from(Prefix::new(96).unwrap()),
+            Ipv6Cidr::new(96)
+        );
+    }
+
+    #[test]
+    fn create_ip_network_from_str() {
+        let network = IpNetwork::try_from("10.1.2.3/20");
+        assert_eq!(
+            network,
+            Ok(IpNetwork::V4(Network::try_from("10.1.0.0/20").unwrap()))
+        );
+
+        let network = IpNetwork::try_from("10.1.2.3 255.255.240.0");
+        assert_eq!(
+            network,
+            Ok(IpNetwork::V4(Network::try_from("10.1.0.0/20").unwrap()))
+        );
+
+        let network = IpNetwork::try_from("2001:db8::1/48");
+        assert_eq!(
+            network,
+            Ok(IpNetwork::V6(
+                Ipv6Network::try_from("2001:db8::/48").unwrap()
+            ))
+        );
+
+        let network = IpNetwork::try_from("10.1.2.3/33");
+        assert_eq!(network, Err(NetworkError::CidrOutOfRangeError));
+
+        let network = IpNetwork::try_from("10.1.2/24");
+        assert_eq!(network, Err(NetworkError::IPv4AddressError));
+
+        let network = IpNetwork::try_from("2001:db8::g/64");
+        assert_eq!(network, Err(NetworkError::IPv6AddressError));
+
+        let network = IpNetwork::try_from("2001:db8::");
+        assert_eq!(network, Err(NetworkError::ParsingError));
+    }
+
+    #[test]
+    fn create_ip_slsm_subnet() {
+        let network = IpNetwork::try_from("192.168.0.0/23").unwrap();
+        let subnet = IpSlsm::new(network.clone(), Prefix::new(24).unwrap()).unwrap();
+        assert_eq!(subnet.base_network(), network);
+        assert_eq!(subnet.prefix(), Prefix::new(24).unwrap());
+        let want_subnets = vec![
+            IpNetwork::try_from("192.168.0.0/24").unwrap(),
+            IpNetwork::try_from("192.168.1.0/24").unwrap(),
+        ];
+        let got_subnets: Vec<IpNetwork> = subnet.collect();
+        assert_eq!(got_subnets, want_subnets);
+
+        let network = IpNetwork::try_from("2001:db8::/63").unwrap();
+        let subnet = IpSlsm::new(network.clone(), Prefix::new(64).unwrap()).unwrap();
+        assert_eq!(subnet.base_network(), network);
+        let want_subnets = vec![
+            IpNetwork::try_from("2001:db8::/64").unwrap(),
+            IpNetwork::try_from("2001:db8:0:1::/64").unwrap(),
+        ];
+        let got_subnets: Vec<IpNetwork> = subnet.collect();
+        assert_eq!(got_subnets, want_subnets);
+
+        let network = IpNetwork::try_from("192.168.0.0/23").unwrap();
+        let subnet = IpSlsm::new(network, Prefix::new(64).unwrap());
+        assert_eq!(subnet.unwrap_err(), NetworkError::CidrOutOfRangeError);
+
+        let network = IpNetwork::try_from("2001:db8::/64").unwrap();
+        let subnet = IpSlsm::new(network, Prefix::new(60).unwrap());
+        assert_eq!(subnet.unwrap_err(), NetworkError::InvalidSubnetCidr);
+    }
+
+    #[test]
+    fn create_ip_vlsm_subnet() {
+        let network = IpNetwork::try_from("10.0.0.0/24").unwrap();
+        let subnet = IpVlsm::new(network.clone(), vec![60, 30, 30, 100]).unwrap();
+        assert_eq!(subnet.base_network(), network);
+        let want_subnets = vec![
+            IpNetwork::try_from("10.0.0.0/25").unwrap(),
+            IpNetwork::try_from("10.0.0.128/26").unwrap(),
+            IpNetwork::try_from("10.0.0.192/27").unwrap(),
+            IpNetwork::try_from("10.0.0.224/27").unwrap(),
+        ];
+        let got_subnets: Vec<IpNetwork> = subnet.collect();
+        assert_eq!(got_subnets, want_subnets);
+
+        let network = IpNetwork::try_from("2001:db8::/120").unwrap();
+        let subnet = IpVlsm::new(network.clone(), vec![60, 30, 32, 100]).unwrap();
+        assert_eq!(subnet.base_network(), network);
+        let want_subnets = vec![
+            IpNetwork::try_from("2001:db8::/121").unwrap(),
+            IpNetwork::try_from("2001:db8::80/122").unwrap(),
+            IpNetwork::try_from("2001:db8::c0/123").unwrap(),
+            IpNetwork::try_from("2001:db8::e0/123").unwrap(),
+        ];
+        let got_subnets: Vec<IpNetwork> = subnet.collect();
+        assert_eq!(got_subnets, want_subnets);
+
+        let network = Ipv6Network::try_from("2001:db8::/64").unwrap();
+        let subnet = Ipv6Vlsm::new(network.clone(), vec![1, 256, 0, 2]).unwrap();
+        assert_eq!(subnet.base_network(), &network);
+        let want_subnets = vec![
+            Ipv6Network::try_from("2001:db8::/120").unwrap(),
+            Ipv6Network::try_from("2001:db8::100/127").unwrap(),
+            Ipv6Network::try_from("2001:db8::102/128").unwrap(),
+            Ipv6Network::try_from("2001:db8::103/128").unwrap(),
+        ];
+        let got_subnets: Vec<Ipv6Network> = subnet.collect();
+        assert_eq!(got_subnets, want_subnets);
+
+        let network = Ipv6Network::try_from("2001:db8::/126").unwrap();
+        let subnet = Ipv6Vlsm::new(network, vec![2, 5]);
+        assert_eq!(
+            subnet.unwrap_err(),
+            NetworkError::InsufficientAddressSpace {
+                index: 1,
+                hosts: 5,
+                required: 10,
+                available: 4,
+            }
+        );
+
+        let network = IpNetwork::try_from("192.168.0.0/30").unwrap();
+        let subnet = IpVlsm::new(network, vec![2, 2, 2]);
+        assert_eq!(
+            subnet.unwrap_err(),
+            NetworkError::InsufficientAddressSpace {
+                index: 2,
+                hosts: 2,
+                required: 6,
+                available: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn get_network_number_of_addresses() {
+        let network = Network::try_from("0.0.0.0/0").unwrap();
+        assert_eq!(network.number_of_addresses(), 4294967296);
+
+        let network = Network::try_from("10.0.0.0/8").unwrap();
+        assert_eq!(network.number_of_addresses(), 16777216);
+
+        let network = Network::try_from("10.0.0.0/31").unwrap();
+        assert_eq!(network.number_of_addresses(), 2);
+
+        let network = Network::try_from("10.0.0.0/32").unwrap();
+        assert_eq!(network.number_of_addresses(), 1);
+    }
+
+    #[test]
+    fn use_subnetting_trait() {
+        fn summary<S: Subnetting>(subnetting: &S) -> (Network, u64, u64, u64, Vec<Network>) {
+            (
+                subnetting.base_network().clone(),
+                subnetting.subnet_count(),
+                subnetting.used_addresses(),
+                subnetting.wasted_addresses(),
+                subnetting.subnets().collect(),
+            )
+        }
+
+        let network = Network::try_from("10.0.0.0/24").unwrap();
+        let mut slsm = Slsm::new(network.clone(), Cidr::new(26).unwrap()).unwrap();
+        // Partially consuming the iterator does not change the partition.
+        slsm.next();
+        let (base_network, subnet_count, used, wasted, subnets) = summary(&slsm);
+        assert_eq!(base_network, network);
+        assert_eq!(subnet_count, 4);
+        assert_eq!(used, 256);
+        assert_eq!(wasted, 0);
+        assert_eq!(
+            subnets,
+            vec![
+                Network::try_from("10.0.0.0/26").unwrap(),
+                Network::try_from("10.0.0.64/26").unwrap(),
+                Network::try_from("10.0.0.128/26").unwrap(),
+                Network::try_from("10.0.0.192/26").unwrap(),
+            ]
+        );
+
+        let network = Network::try_from("0.0.0.0/0").unwrap();
+        let slsm = Slsm::new(network, Cidr::new(8).unwrap()).unwrap();
+        assert_eq!(slsm.subnet_count(), 256);
+        assert_eq!(slsm.used_addresses(), 4294967296);
+        assert_eq!(slsm.wasted_addresses(), 0);
+
+        let network = Network::try_from("10.0.0.0/24").unwrap();
+        let mut vlsm = Vlsm::new(network.clone(), vec![60, 10, 2]).unwrap();
+        vlsm.next();
+        vlsm.next();
+        let (base_network, subnet_count, used, wasted, subnets) = summary(&vlsm);
+        assert_eq!(base_network, network);
+        assert_eq!(subnet_count, 3);
+        assert_eq!(used, 82);
+        assert_eq!(wasted, 174);
+        assert_eq!(
+            subnets,
+            vec![
+                Network::try_from("10.0.0.0/26").unwrap(),
+                Network::try_from("10.0.0.64/28").unwrap(),
+                Network::try_from("10.0.0.80/31").unwrap(),
+            ]
+        );
+
+        let vlsm = Vlsm::new(network, vec![]).unwrap();
+        assert_eq!(vlsm.subnet_count(), 0);
+        assert_eq!(vlsm.used_addresses(), 0);
+        assert_eq!(vlsm.wasted_addresses(), 256);
+    }
+
+    #[test]
+    fn display_network() {
+        let network = Network::try_from("10.1.2.3 255.255.240.0").unwrap();
+        assert_eq!(network.to_string(), "10.1.0.0/20");
+        assert_eq!(network.netmask(), Ipv4Addr::new(255, 255, 240, 0));
+
+        let network = Network::try_from("0.0.0.0/0").unwrap();
+        assert_eq!(network.to_string(), "0.0.0.0/0");
+        assert_eq!(network.netmask(), Ipv4Addr::new(0, 0, 0, 0));
+
+        let network = Network::try_from("10.1.2.3/32").unwrap();
+        assert_eq!(network.to_string(), "10.1.2.3/32");
+        assert_eq!(network.netmask(), Ipv4Addr::new(255, 255, 255, 255));
+
+        let network = Ipv6Network::try_from("2001:db8:0:0:1::1/64").unwrap();
+        assert_eq!(network.to_string(), "2001:db8::/64");
+
+        let network = IpNetwork::try_from("2001:db8::1/128").unwrap();
+        assert_eq!(network.to_string(), "2001:db8::1/128");
+
+        let network = IpNetwork::try_from("192.168.1.1/24").unwrap();
+        assert_eq!(network.to_string(), "192.168.1.0/24");
+
+        let error = NetworkError::InsufficientAddressSpace {
+            index: 1,
+            hosts: 200,
+            required: 384,
+            available: 256,
+        };
+        assert_eq!(
+            error.to_string(),
+            "requirement 1 for 200 hosts does not fit: 384 addresses required, 256 available"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_and_deserialize() {
+        let network = Network::try_from("10.1.2.3/20").unwrap();
+        assert_eq!(serde_json::to_string(&network).unwrap(), "\"10.1.0.0/20\"");
+        assert_eq!(
+            serde_json::from_str::<Network>("\"10.1.0.0/20\"").unwrap(),
+            network
+        );
+        assert_eq!(
+            serde_json::from_str::<Network>("\"10.1.2.3 255.255.240.0\"").unwrap(),
+            network
+        );
+        assert!(serde_json::from_str::<Network>("\"10.1.2.3/33\"").is_err());
+        assert!(serde_json::from_str::<Network>("20").is_err());
+
+        let cidr = Cidr::new(24).unwrap();
+        assert_eq!(serde_json::to_string(&cidr).unwrap(), "24");
+        assert_eq!(serde_json::from_str::<Cidr>("24").unwrap(), cidr);
+        assert!(serde_json::from_str::<Cidr>("33").is_err());
+
+        let network = IpNetwork::try_from("2001:db8::1/64").unwrap();
+        assert_eq!(
+            serde_json::to_string(&network).unwrap(),
+            "\"2001:db8::/64\""
+        );
+        assert_eq!(
+            serde_json::from_str::<IpNetwork>("\"2001:db8::/64\"").unwrap(),
+            network
+        );
+        assert!(serde_json::from_str::<Ipv6Cidr>("129").is_err());
+
+        let range = Ipv4Range::try_from("10.0.0.5-10.0.1.200").unwrap();
+        let json = serde_json::to_string(&range).unwrap();
+        assert_eq!(json, "\"10.0.0.5-10.0.1.200\"");
+        assert_eq!(serde_json::from_str::<Ipv4Range>(&json).unwrap(), range);
+        assert!(serde_json::from_str::<Ipv4Range>("\"10.0.0.6-10.0.0.5\"").is_err());
+        assert_eq!(
+            serde_json::from_str::<Prefix>("96").unwrap(),
+            Prefix::new(96).unwrap()
+        );
+
+        let slsm = Slsm::new(Network::try_from("10.0.0.0/24").unwrap(), cidr).unwrap();
+        let json = serde_json::to_string(&slsm).unwrap();
+        assert_eq!(json, "{\"base_network\":\"10.0.0.0/24\",\"cidr\":24}");
+        assert_eq!(serde_json::from_str::<Slsm>(&json).unwrap(), slsm);
+        assert!(
+            serde_json::from_str::<Slsm>("{\"base_network\":\"10.0.0.0/24\",\"cidr\":16}").is_err()
+        );
+
+        let vlsm = Vlsm::new(Network::try_from("10.0.0.0/24").unwrap(), vec![20, 100]).unwrap();
+        let json = serde_json::to_string(&vlsm).unwrap();
+        assert_eq!(
+            json,
+            "{\"base_network\":\"10.0.0.0/24\",\"required_hosts\":[20,100],\"subnets\":[\"10.0.0.128/27\",\"10.0.0.0/25\"]}"
+        );
+        assert_eq!(serde_json::from_str::<Vlsm>(&json).unwrap(), vlsm);
+        assert!(serde_json::from_str::<Vlsm>(
+            "{\"base_network\":\"10.0.0.0/24\",\"required_hosts\":[200,100]}"
+        )
+        .is_err());
+
+        let vlsm = Vlsm::with_requirements(
+            Network::try_from("10.0.0.0/24").unwrap(),
+            vec![
+                Requirement::hosts("users", 20).with_metadata("vlan", "10"),
+                Requirement::hosts("servers", 100),
+            ],
+        )
+        .unwrap();
+        let json = serde_json::to_string(&vlsm).unwrap();
+        assert_eq!(
+            json,
+            "{\"base_network\":\"10.0.0.0/24\",\"requirements\":[{\"label\":\"users\",\"hosts\":20,\"metadata\":{\"vlan\":\"10\"}},{\"label\":\"servers\",\"hosts\":100}],\"subnets\":[\"10.0.0.128/27\",\"10.0.0.0/25\"]}"
+        );
+        assert_eq!(serde_json::from_str::<Vlsm>(&json).unwrap(), vlsm);
+        assert!(serde_json::from_str::<Vlsm>(
+            "{\"base_network\":\"10.0.0.0/24\",\"required_hosts\":[20],\"requirements\":[]}"
+        )
+        .is_err());
+
+        let vlsm = Vlsm::with_requirements(
+            Network::try_from("10.0.0.0/24").unwrap(),
+            vec![
+                Requirement::cidr("link", Cidr::new(31).unwrap()),
+                Requirement::pinned("gateway", Network::try_from("10.0.0.0/28").unwrap()),
+            ],
+        )
+        .unwrap();
+        let json = serde_json::to_string(&vlsm).unwrap();
+        assert_eq!(
+            json,
+            "{\"base_network\":\"10.0.0.0/24\",\"requirements\":[{\"label\":\"link\",\"cidr\":31},{\"label\":\"gateway\",\"network\":\"10.0.0.0/28\"}],\"subnets\":[\"10.0.0.16/31\",\"10.0.0.0/28\"]}"
+        );
+        assert_eq!(serde_json::from_str::<Vlsm>(&json).unwrap(), vlsm);
+
+        let vlsm = Vlsm::with_requirements(
+            Network::try_from("10.0.0.0/24").unwrap(),
+            vec![Requirement::hosts("", 40)
+                .with_growth(Growth::Factor(NonZeroU32::new(2).unwrap()))
+                .with_buddy_reserved()],
+        )
+        .unwrap();
+        let json = serde_json::to_string(&vlsm).unwrap();
+        assert_eq!(
+            json,
+            "{\"base_network\":\"10.0.0.0/24\",\"requirements\":[{\"label\":\"\",\"hosts\":40,\"growth\":{\"factor\":2},\"reserve_buddy\":true}],\"subnets\":[\"10.0.0.0/25\"]}"
+        );
+        assert_eq!(serde_json::from_str::<Vlsm>(&json).unwrap(), vlsm);
+        // The allocated subnets are restored, so a Vlsm keeps the subnets its placement chose.
+        let requirements = vec![
+            Requirement::pinned("gateway", Network::try_from("10.0.0.64/27").unwrap()),
+            Requirement::hosts("users", 20),
+            Requirement::hosts("servers", 10).with_buddy_reserved(),
+        ];
+        for placement in [&BestFit as &dyn Placement, &TopDown] {
+            let vlsm = Vlsm::with_placement(
+                Network::try_from("10.0.0.0/24").unwrap(),
+                requirements.clone(),
+                placement,
+            )
+            .unwrap();
+            let json = serde_json::to_string(&vlsm).unwrap();
+            let restored = serde_json::from_str::<Vlsm>(&json).unwrap();
+            assert_eq!(restored, vlsm);
+            assert_eq!(restored.report(), vlsm.report());
+        }
+        let vlsm = Vlsm::with_placement(
+            Network::try_from("10.0.0.0/24").unwrap(),
+            vec![Requirement::hosts("", 10)],
+            &TopDown,
+        )
+        .unwrap();
+        let json = serde_json::to_string(&vlsm).unwrap();
+        assert_eq!(
+            json,
+            "{\"base_network\":\"10.0.0.0/24\",\"required_hosts\":[10],\"subnets\":[\"10.0.0.240/28\"]}"
+        );
+        assert_eq!(serde_json::from_str::<Vlsm>(&json).unwrap(), vlsm);
+
+        // Without subnets the Vlsm is planned with first-fit placement.
+        assert_eq!(
+            serde_json::from_str::<Vlsm>(
+                "{\"base_network\":\"10.0.0.0/24\",\"required_hosts\":[10]}"
+            )
+            .unwrap()
+            .collect::<Vec<_>>(),
+            vec![Network::try_from("10.0.0.0/28").unwrap()]
+        );
+        for json in [
+            "{\"base_network\":\"10.0.0.0/24\",\"required_hosts\":[10,10],\"subnets\":[\"10.0.0.0/28\"]}",
+            "{\"base_network\":\"10.0.0.0/24\",\"required_hosts\":[10],\"subnets\":[\"10.0.0.0/27\"]}",
+            "{\"base_network\":\"10.0.0.0/24\",\"required_hosts\":[10],\"subnets\":[\"10.0.1.0/28\"]}",
+            "{\"base_network\":\"10.0.0.0/24\",\"required_hosts\":[10,10],\"subnets\":[\"10.0.0.0/28\",\"10.0.0.0/28\"]}",
+            "{\"base_network\":\"10.0.0.0/24\",\"requirements\":[{\"label\":\"gateway\",\"network\":\"10.0.0.0/28\"}],\"subnets\":[\"10.0.0.16/28\"]}",
+        ] {
+            assert!(serde_json::from_str::<Vlsm>(json).is_err(), "{json}");
+        }
+
+        // A growth factor of zero would shrink the subnet, so it is rejected.
+        assert!(serde_json::from_str::<Requirement>(
+            "{\"label\":\"vlan\",\"hosts\":300,\"growth\":{\"factor\":0}}"
+        )
+        .is_err());
+
+        let error = NetworkError::ParsingError;
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(json, "{\"error\":\"ParsingError\"}");
+        assert_eq!(serde_json::from_str::<NetworkError>(&json).unwrap(), error);
+
+        let error = NetworkError::InsufficientAddressSpace {
+            index: 1,
+            hosts: 100,
+            required: 384,
+            available: 256,
+        };
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(
+            json,
+            "{\"error\":\"InsufficientAddressSpace\",\"details\":{\"index\":1,\"hosts\":100,\"required\":384,\"available\":256}}"
+        );
+        assert_eq!(serde_json::from_str::<NetworkError>(&json).unwrap(), error);
+    }
+
+    #[test]
+    fn network_containment() {
+        let network = Network::try_from("10.1.0.0/20").unwrap();
+        assert!(network.contains(Ipv4Addr::new(10, 1, 0, 0)));
+        assert!(network.contains(Ipv4Addr::new(10, 1, 7, 12)));
+        assert!(network.contains(Ipv4Addr::new(10, 1, 15, 255)));
+        assert!(!network.contains(Ipv4Addr::new(10, 1, 16, 0)));
+        assert!(!network.contains(Ipv4Addr::new(10, 0, 255, 255)));
+
+        let network = Network::try_from("0.0.0.0/0").unwrap();
+        assert!(network.contains(Ipv4Addr::new(0, 0, 0, 0)));
+        assert!(network.contains(Ipv4Addr::new(255, 255, 255, 255)));
+
+        let network = Network::try_from("10.1.2.3/32").unwrap();
+        assert!(network.contains(Ipv4Addr::new(10, 1, 2, 3)));
+        assert!(!network.contains(Ipv4Addr::new(10, 1, 2, 4)));
+
+        let supernet = Network::try_from("10.1.0.0/16").unwrap();
+        let subnet = Network::try_from("10.1.32.0/19").unwrap();
+        let other = Network::try_from("10.2.0.0/16").unwrap();
+        assert!(supernet.contains_network(&subnet));
+        assert!(supernet.contains_network(&supernet));
+        assert!(!subnet.contains_network(&supernet));
+        assert!(!supernet.contains_network(&other));
+        assert!(supernet.overlaps(&subnet));
+        assert!(subnet.overlaps(&supernet));
+        assert!(!supernet.overlaps(&other));
+        assert!(!subnet.overlaps(&other));
+    }
+
+    #[test]
+    fn network_relation() {
+        let network = Network::try_from("10.1.0.0/16").unwrap();
+        let relation = |s: &str| network.relation(&Network::try_from(s).unwrap());
+        assert_eq!(relation("10.1.255.255/16"), Relation::Equal);
+        assert_eq!(relation("10.0.0.0/8"), Relation::Subnet);
+        assert_eq!(relation("0.0.0.0/0"), Relation::Subnet);
+        assert_eq!(relation("10.1.128.0/17"), Relation::Supernet);
+        assert_eq!(relation("10.1.2.3/32"), Relation::Supernet);
+        assert_eq!(relation("10.2.0.0/16"), Relation::Adjacent);
+        assert_eq!(relation("10.0.255.255/32"), Relation::Adjacent);
+        assert_eq!(relation("10.2.0.1/32"), Relation::Disjoint);
+        assert_eq!(relation("10.3.0.0/16"), Relation::Disjoint);
+        assert_eq!(relation("192.168.0.0/24"), Relation::Disjoint);
+
+        let network = Network::try_from("255.255.255.255/32").unwrap();
+        let other = Network::try_from("0.0.0.0/32").unwrap();
+        assert_eq!(network.relation(&other), Relation::Disjoint);
+        assert_eq!(other.relation(&network), Relation::Disjoint);
+        let other = Network::try_from("255.255.255.254/32").unwrap();
+        assert_eq!(network.relation(&other), Relation::Adjacent);
+        assert_eq!(other.relation(&network), Relation::Adjacent);
+    }
+
+    #[test]
+    fn collapse_network_list() {
+        let networks = |list: &[&str]| -> Vec<Network> {
+            list.iter()
+                .map(|s| Network::try_from(*s).unwrap())
+                .collect()
+        };
+
+        let got = collapse_networks(networks(&[
+            "192.0.2.0/26",
+            "192.0.2.64/26",
+            "192.0.2.128/26",
+            "192.0.2.192/26",
+        ]));
+        assert_eq!(got, networks(&["192.0.2.0/24"]));
+
+        let got = collapse_networks(networks(&[
+            "10.0.3.0/24",
+            "10.0.0.0/24",
+            "10.0.1.0/24",
+            "10.0.1.128/25",
+            "10.0.2.7/32",
+        ]));
+        assert_eq!(
+            got,
+            networks(&["10.0.0.0/23", "10.0.2.7/32", "10.0.3.0/24"])
+        );
+
+        // Adjacent networks that are not siblings stay apart.
+        let got = collapse_networks(networks(&["10.0.1.0/24", "10.0.2.0/24"]));
+        assert_eq!(got, networks(&["10.0.1.0/24", "10.0.2.0/24"]));
+
+        let got = collapse_networks(networks(&[
+            "10.0.0.0/8",
+            "10.20.30.0/24",
+            "10.0.0.0/8",
+            "11.0.0.0/8",
+        ]));
+        assert_eq!(got, networks(&["10.0.0.0/7"]));
+
+        let got = collapse_networks(networks(&["0.0.0.0/1", "128.0.0.0/1"]));
+        assert_eq!(got, networks(&["0.0.0.0/0"]));
+
+        let got = collapse_networks(networks(&[
+            "255.255.255.255/32",
+            "255.255.255.254/32",
+            "0.0.0.0/32",
+        ]));
+        assert_eq!(got, networks(&["0.0.0.0/32", "255.255.255.254/31"]));
+
+        let got = collapse_networks(networks(&[
+            "192.168.0.1/32",
+            "192.168.0.2/31",
+            "192.168.0.4/30",
+            "192.168.0.8/29",
+            "192.168.0.16/28",
+        ]));
+        assert_eq!(
+            got,
+            networks(&[
+                "192.168.0.1/32",
+                "192.168.0.2/31",
+                "192.168.0.4/30",
+                "192.168.0.8/29",
+                "192.168.0.16/28",
+            ])
+        );
+
+        let got = collapse_networks(Vec::new());
+        assert_eq!(got, vec![]);
+    }
+
+    #[test]
+    fn exclude_networks() {
+        let networks = |list: &[&str]| -> Vec<Network> {
+            list.iter()
+                .map(|s| Network::try_from(*s).unwrap())
+                .collect()
+        };
+
+        let base = Network::try_from("10.0.0.0/24").unwrap();
+        let got = base.exclude(&networks(&["10.0.0.64/26"])).unwrap();
+        assert_eq!(got, networks(&["10.0.0.0/26", "10.0.0.128/25"]));
+
+        let got = base.exclude(&networks(&["10.0.0.7/32"])).unwrap();
+        assert_eq!(
+            got,
+            networks(&[
+                "10.0.0.0/30",
+                "10.0.0.4/31",
+                "10.0.0.6/32",
+                "10.0.0.8/29",
+                "10.0.0.16/28",
+                "10.0.0.32/27",
+                "10.0.0.64/26",
+                "10.0.0.128/25",
+            ])
+        );
+
+        let got = base
+            .exclude(&networks(&[
+                "10.0.0.128/25",
+                "10.0.0.0/26",
+                "10.0.0.192/26",
+                "10.0.0.96/27",
+            ]))
+            .unwrap();
+        assert_eq!(got, networks(&["10.0.0.64/27"]));
+
+        let got = base.exclude(&networks(&["10.0.0.0/24"])).unwrap();
+        assert_eq!(got, vec![]);
+
+        let got = base.exclude(&[]).unwrap();
+        assert_eq!(got, vec![base.clone()]);
+
+        let base = Network::try_from("0.0.0.0/0").unwrap();
+        let got = base
+            .exclude(&networks(&["0.0.0.0/32", "255.255.255.255/32"]))
+            .unwrap();
+        assert_eq!(got.len(), 62);
+        assert_eq!(got[0], Network::try_from("0.0.0.1/32").unwrap());
+        assert_eq!(got[61], Network::try_from("255.255.255.254/32").unwrap());
+
+        let base = Network::try_from("10.0.0.0/24").unwrap();
+        let got = base.exclude(&networks(&["10.0.0.0/25", "10.0.1.0/25"]));
+        assert_eq!(
+            got,
+            Err(NetworkError::NetworkOutsideBase {
+                network: Network::try_from("10.0.1.0/25").unwrap()
+            })
+        );
+
+        let got = base.exclude(&networks(&["10.0.0.0/23"]));
+        assert_eq!(
+            got,
+            Err(NetworkError::NetworkOutsideBase {
+                network: Network::try_from("10.0.0.0/23").unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn ip_set_operations() {
+        let networks = |list: &[&str]| -> Vec<Network> {
+            list.iter()
+                .map(|s| Network::try_from(*s).unwrap())
+                .collect()
+        };
+
+        let mut allowed = IpSet::new();
+        assert!(allowed.is_empty());
+        allowed.insert_network(&Network::try_from("10.0.0.0/24").unwrap());
+        allowed.insert_range(Ipv4Addr::new(10, 0, 1, 0)..=Ipv4Addr::new(10, 0, 1, 9));
+        allowed.insert_range(Ipv4Addr::new(10, 0, 5, 0)..=Ipv4Addr::new(10, 0, 4, 0));
+        assert!(!allowed.is_empty());
+        assert_eq!(allowed.len(), 266);
+        assert!(allowed.contains(Ipv4Addr::new(10, 0, 0, 0)));
+        assert!(allowed.contains(Ipv4Addr::new(10, 0, 1, 9)));
+        assert!(!allowed.contains(Ipv4Addr::new(10, 0, 1, 10)));
+        assert!(!allowed.contains(Ipv4Addr::new(9, 255, 255, 255)));
+        assert!(!allowed.contains(Ipv4Addr::new(10, 0, 4, 0)));
+        assert!(allowed.contains_network(&Network::try_from("10.0.0.128/25").unwrap()));
+        assert!(allowed.contains_network(&Network::try_from("10.0.1.8/31").unwrap()));
+        assert!(!allowed.contains_network(&Network::try_from("10.0.1.8/29").unwrap()));
+        assert!(!allowed.contains_network(&Network::try_from("10.0.0.0/23").unwrap()));
+        assert_eq!(
+            allowed.ranges().collect::<Vec<_>>(),
+            vec![Ipv4Addr::new(10, 0, 0, 0)..=Ipv4Addr::new(10, 0, 1, 9)]
+        );
+        assert_eq!(
+            allowed.networks().collect::<Vec<_>>(),
+            networks(&["10.0.0.0/24", "10.0.1.0/29", "10.0.1.8/31"])
+        );
+
+        let denied: IpSet = networks(&["10.0.0.64/26", "10.0.1.0/24", "192.168.0.0/16"])
+            .into_iter()
+            .collect();
+        assert_eq!(
+            allowed.difference(&denied).networks().collect::<Vec<_>>(),
+            networks(&["10.0.0.0/26", "10.0.0.128/25"])
+        );
+        assert_eq!(
+            allowed.intersection(&denied).networks().collect::<Vec<_>>(),
+            networks(&["10.0.0.64/26", "10.0.1.0/29", "10.0.1.8/31"])
+        );
+        assert_eq!(
+            allowed.union(&denied).networks().collect::<Vec<_>>(),
+            networks(&["10.0.0.0/23", "192.168.0.0/16"])
+        );
+        assert_eq!(allowed.intersection(&IpSet::new()), IpSet::new());
+        assert_eq!(allowed.union(&IpSet::new()), allowed);
+
+        let complement = allowed.complement();
+        assert_eq!(complement.len(), (1_u64 << 32) - 266);
+        assert!(complement.contains(Ipv4Addr::new(0, 0, 0, 0)));
+        assert!(complement.contains(Ipv4Addr::new(255, 255, 255, 255)));
+        assert!(!complement.contains(Ipv4Addr::new(10, 0, 0, 5)));
+        assert_eq!(complement.complement(), allowed);
+        assert_eq!(allowed.intersection(&complement), IpSet::new());
+
+        let everything = IpSet::new().complement();
+        assert_eq!(everything.len(), 1 << 32);
+        assert_eq!(
+            everything.networks().collect::<Vec<_>>(),
+            networks(&["0.0.0.0/0"])
+        );
+        assert_eq!(everything.complement(), IpSet::new());
+
+        let mut set = IpSet::from(Network::try_from("10.0.0.0/25").unwrap());
+        set.extend(networks(&["10.0.0.128/25"]));
+        assert_eq!(
+            set.networks().collect::<Vec<_>>(),
+            networks(&["10.0.0.0/24"])
+        );
+        let set = IpSet::from(Ipv4Addr::new(10, 0, 0, 0)..=Ipv4Addr::new(10, 0, 0, 255));
+        assert_eq!(
+            set.networks().collect::<Vec<_>>(),
+            networks(&["10.0.0.0/24"])
+        );
+    }
+
+    #[test]
+    fn prefix_map_lookups() {
+        let network = |s: &str| Network::try_from(s).unwrap();
+
+        let mut map = PrefixMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.longest_match(Ipv4Addr::new(10, 1, 2, 3)), None);
+        assert_eq!(map.insert(network("10.0.0.0/8"), "a"), None);
+        assert_eq!(map.insert(network("10.1.0.0/16"), "b"), None);
+        assert_eq!(map.insert(network("10.1.2.0/24"), "c"), None);
+        assert_eq!(map.insert(network("10.1.2.128/25"), "d"), None);
+        assert_eq!(map.insert(network("10.128.0.0/9"), "e"), None);
+        assert_eq!(map.insert(network("192.168.0.0/16"), "f"), None);
+        assert_eq!(map.insert(network("10.1.0.0/16"), "g"), Some("b"));
+        assert_eq!(map.len(), 6);
+
+        assert_eq!(map.get(&network("10.1.0.0/16")), Some(&"g"));
+        assert_eq!(map.get(&network("10.1.0.0/17")), None);
+        assert_eq!(map.get(&network("10.0.0.0/7")), None);
+        assert_eq!(map.get(&network("0.0.0.0/0")), None);
+        assert!(map.contains_key(&network("10.1.2.128/25")));
+        *map.get_mut(&network("10.128.0.0/9")).unwrap() = "h";
+
+        assert_eq!(
+            map.longest_match(Ipv4Addr::new(10, 1, 2, 200)),
+            Some((network("10.1.2.128/25"), &"d"))
+        );
+        assert_eq!(
+            map.longest_match(Ipv4Addr::new(10, 1, 2, 100)),
+            Some((network("10.1.2.0/24"), &"c"))
+        );
+        assert_eq!(
+            map.longest_match(Ipv4Addr::new(10, 1, 3, 1)),
+            Some((network("10.1.0.0/16"), &"g"))
+        );
+        assert_eq!(
+            map.longest_match(Ipv4Addr::new(10, 200, 0, 1)),
+            Some((network("10.128.0.0/9"), &"h"))
+        );
+        assert_eq!(
+            map.longest_match(Ipv4Addr::new(10, 2, 0, 1)),
+            Some((network("10.0.0.0/8"), &"a"))
+        );
+        assert_eq!(map.longest_match(Ipv4Addr::new(11, 0, 0, 1)), None);
+
+        assert_eq!(
+            map.covering(&network("10.1.2.192/26")).collect::<Vec<_>>(),
+            vec![
+                (network("10.0.0.0/8"), &"a"),
+                (network("10.1.0.0/16"), &"g"),
+                (network("10.1.2.0/24"), &"c"),
+                (network("10.1.2.128/25"), &"d"),
+            ]
+        );
+        assert_eq!(
+            map.covered(&network("10.0.0.0/8")).collect::<Vec<_>>(),
+            vec![
+                (network("10.0.0.0/8"), &"a"),
+                (network("10.1.0.0/16"), &"g"),
+                (network("10.1.2.0/24"), &"c"),
+                (network("10.1.2.128/25"), &"d"),
+                (network("10.128.0.0/9"), &"h"),
+            ]
+        );
+        assert_eq!(
+            map.covered(&network("10.1.2.0/23")).collect::<Vec<_>>(),
+            vec![
+                (network("10.1.2.0/24"), &"c"),
+                (network("10.1.2.128/25"), &"d"),
+            ]
+        );
+        assert_eq!(map.covered(&network("10.64.0.0/10")).count(), 0);
+        assert_eq!(map.covered(&network("0.0.0.0/0")).count(), 6);
+        assert_eq!(map.iter().count(), 6);
+
+        assert_eq!(map.remove(&network("10.1.0.0/17")), None);
+        assert_eq!(map.remove(&network("10.1.2.0/24")), Some("c"));
+        assert_eq!(map.remove(&network("10.1.2.0/24")), None);
+        assert_eq!(
+            map.longest_match(Ipv4Addr::new(10, 1, 2, 100)),
+            Some((network("10.1.0.0/16"), &"g"))
+        );
+        assert_eq!(map.remove(&network("10.1.2.128/25")), Some("d"));
+        assert_eq!(map.remove(&network("10.1.0.0/16")), Some("g"));
+        assert_eq!(map.len(), 3);
+        assert_eq!(
+            map.longest_match(Ipv4Addr::new(10, 1, 2, 200)),
+            Some((network("10.0.0.0/8"), &"a"))
+        );
+
+        map.insert(network("0.0.0.0/0"), "default");
+        map.insert(network("10.1.2.3/32"), "host");
+        assert_eq!(
+            map.longest_match(Ipv4Addr::new(11, 0, 0, 1)),
+            Some((network("0.0.0.0/0"), &"default"))
+        );
+        assert_eq!(
+            map.longest_match(Ipv4Addr::new(10, 1, 2, 3)),
+            Some((network("10.1.2.3/32"), &"host"))
+        );
+        assert_eq!(map.remove(&network("0.0.0.0/0")), Some("default"));
+        assert_eq!(map.longest_match(Ipv4Addr::new(11, 0, 0, 1)), None);
+    }
+
+    #[test]
+    fn prefix_map_matches_linear_scan() {
+        // Compare against a linear scan using a deterministic pseudo-random sequence of prefixes.
+        let mut seed = 0x2545f491_u32;
+        let mut random = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        };
+
+        let mut map = PrefixMap::new();
+        let mut entries: Vec<(Network, u32)> = Vec::new();
+        for i in 0..2000 {
+            // Keep the addresses within a /12 so that prefixes nest and overlap.
+            let address = Ipv4Addr::from(0x0a000000 | (random() & 0x000fffff));
+            let cidr = Cidr::new(12 + (random() % 21) as u8).unwrap();
+            let network = Network::new(address, cidr).unwrap();
+            let previous = entries.iter().position(|(n, _)| *n == network);
+            let want = previous.map(|p| entries.remove(p).1);
+            entries.push((network.clone(), i));
+            assert_eq!(map.insert(network, i), want);
+        }
+        for _ in 0..500 {
+            let index = random() as usize % entries.len();
+            let (network, value) = entries.swap_remove(index);
+            assert_eq!(map.remove(&network), Some(value));
+        }
+        assert_eq!(map.len(), entries.len());
+
+        entries.sort();
+        let got: Vec<(Network, u32)> = map.iter().map(|(n, v)| (n, *v)).collect();
+        assert_eq!(got, entries);
+
+        for _ in 0..2000 {
+            let address = Ipv4Addr::from(0x0a000000 | (random() & 0x000fffff));
+            let want = entries
+                .iter()
+                .filter(|(n, _)| n.contains(address))
+                .max_by_key(|(n, _)| n.cidr())
+                .map(|(n, v)| (n.clone(), v));
+            assert_eq!(map.longest_match(address), want);
+
+            let query =
+                Network::new(address, Cidr::new(12 + (random() % 21) as u8).unwrap()).unwrap();
+            let want: Vec<(Network, &u32)> = entries
+                .iter()
+                .filter(|(n, _)| query.contains_network(n))
+                .map(|(n, v)| (n.clone(), v))
+                .collect();
+            assert_eq!(map.covered(&query).collect::<Vec<_>>(), want);
+            let want: Vec<(Network, &u32)> = entries
+                .iter()
+                .filter(|(n, _)| n.contains_network(&query))
+                .map(|(n, v)| (n.clone(), v))
+                .collect();
+            assert_eq!(map.covering(&query).collect::<Vec<_>>(), want);
+        }
+    }
+
+    #[test]
+    fn create_ipv4_range() {
+        let range = Ipv4Range::try_from("10.0.0.5-10.0.1.200").unwrap();
+        assert_eq!(range.first(), Ipv4Addr::new(10, 0, 0, 5));
+        assert_eq!(range.last(), Ipv4Addr::new(10, 0, 1, 200));
+        assert_eq!(range.number_of_addresses(), 452);
+        assert_eq!(range.to_string(), "10.0.0.5-10.0.1.200");
+        assert!(range.contains(Ipv4Addr::new(10, 0, 0, 5)));
+        assert!(range.contains(Ipv4Addr::new(10, 0, 0, 255)));
+        assert!(range.contains(Ipv4Addr::new(10, 0, 1, 200)));
+        assert!(!range.contains(Ipv4Addr::new(10, 0, 0, 4)));
+        assert!(!range.contains(Ipv4Addr::new(10, 0, 1, 201)));
+
+        let range = Ipv4Range::try_from(" 10.0.0.5 - 10.0.0.5 ").unwrap();
+        assert_eq!(range.number_of_addresses(), 1);
+
+        let range = Ipv4Range::try_from("0.0.0.0-255.255.255.255").unwrap();
+        assert_eq!(range.number_of_addresses(), 1 << 32);
+
+        let range = Ipv4Range::new(Ipv4Addr::new(10, 0, 0, 6), Ipv4Addr::new(10, 0, 0, 5));
+        assert_eq!(range, Err(NetworkError::InvalidRange));
+
+        let range = Ipv4Range::try_from("10.0.0.6-10.0.0.5");
+        assert_eq!(range, Err(NetworkError::InvalidRange));
+
+        let range = Ipv4Range::try_from("10.0.0.5");
+        assert_eq!(range, Err(NetworkError::ParsingError));
+
+        let range = Ipv4Range::try_from("");
+        assert_eq!(range, Err(NetworkError::ParsingError));
+
+        let range = Ipv4Range::try_from("10.0.0-10.0.0.5");
+        assert_eq!(range, Err(NetworkError::IPv4AddressError));
+
+        let range = Ipv4Range::try_from("10.0.0.1-10.0.0.256");
+        assert_eq!(range, Err(NetworkError::IPv4AddressError));
+
+        let range = Ipv4Range::try_from("10.0.0.1-");
+        assert_eq!(range, Err(NetworkError::IPv4AddressError));
+    }
+
+    #[test]
+    fn convert_ipv4_range_to_networks() {
+        let networks = |list: &[&str]| -> Vec<Network> {
+            list.iter()
+                .map(|s| Network::try_from(*s).unwrap())
+                .collect()
+        };
+
+        let range = Ipv4Range::try_from("10.0.0.5-10.0.1.200").unwrap();
+        assert_eq!(
+            range.to_networks(),
+            networks(&[
+                "10.0.0.5/32",
+                "10.0.0.6/31",
+                "10.0.0.8/29",
+                "10.0.0.16/28",
+                "10.0.0.32/27",
+                "10.0.0.64/26",
+                "10.0.0.128/25",
+                "10.0.1.0/25",
+                "10.0.1.128/26",
+                "10.0.1.192/29",
+                "10.0.1.200/32",
+            ])
+        );
+
+        let range = Ipv4Range::try_from("0.0.0.0-255.255.255.255").unwrap();
+        assert_eq!(range.to_networks(), networks(&["0.0.0.0/0"]));
+
+        let range = Ipv4Range::try_from("255.255.255.255-255.255.255.255").unwrap();
+        assert_eq!(range.to_networks(), networks(&["255.255.255.255/32"]));
+
+        let network = Network::try_from("172.16.4.0/22").unwrap();
+        let range = Ipv4Range::from(network.clone());
+        assert_eq!(range.to_string(), "172.16.4.0-172.16.7.255");
+        assert_eq!(range.to_networks(), vec![network]);
+
+        let range = Ipv4Range::from(Network::try_from("172.16.4.9/32").unwrap());
+        assert_eq!(range.to_string(), "172.16.4.9-172.16.4.9");
+
+        let set = IpSet::from(Ipv4Range::try_from("10.0.0.0-10.0.0.127").unwrap());
+        assert_eq!(
+            set.networks().collect::<Vec<_>>(),
+            networks(&["10.0.0.0/25"])
+        );
+    }
+
+    #[test]
+    fn iterate_ipv4_range() {
+        let range = Ipv4Range::try_from("10.0.0.254-10.0.1.1").unwrap();
+        assert_eq!(
+            range.iter().collect::<Vec<_>>(),
+            vec![
+                Ipv4Addr::new(10, 0, 0, 254),
+                Ipv4Addr::new(10, 0, 0, 255),
+                Ipv4Addr::new(10, 0, 1, 0),
+                Ipv4Addr::new(10, 0, 1, 1),
+            ]
+        );
+        assert_eq!(
+            range.into_iter().rev().collect::<Vec<_>>(),
+            vec![
+                Ipv4Addr::new(10, 0, 1, 1),
+                Ipv4Addr::new(10, 0, 1, 0),
+                Ipv4Addr::new(10, 0, 0, 255),
+                Ipv4Addr::new(10, 0, 0, 254),
+            ]
+        );
+
+        let mut addresses = range.iter();
+        assert_eq!(addresses.size_hint(), (4, Some(4)));
+        assert_eq!(addresses.next(), Some(Ipv4Addr::new(10, 0, 0, 254)));
+        assert_eq!(addresses.next_back(), Some(Ipv4Addr::new(10, 0, 1, 1)));
+        assert_eq!(addresses.size_hint(), (2, Some(2)));
+        assert_eq!(addresses.nth(1), Some(Ipv4Addr::new(10, 0, 1, 0)));
+        assert_eq!(addresses.next(), None);
+        assert_eq!(addresses.next_back(), None);
+
+        let range = Ipv4Range::try_from("255.255.255.254-255.255.255.255").unwrap();
+        assert_eq!(
+            range.iter().collect::<Vec<_>>(),
+            vec![
+                Ipv4Addr::new(255, 255, 255, 254),
+                Ipv4Addr::new(255, 255, 255, 255),
+            ]
+        );
+
+        let range = Ipv4Range::try_from("0.0.0.0-255.255.255.255").unwrap();
+        let mut addresses = range.iter();
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(addresses.len(), 1 << 32);
+        assert_eq!(addresses.nth(1 << 31), Some(Ipv4Addr::new(128, 0, 0, 0)));
+        assert_eq!(
+            addresses.nth_back(1),
+            Some(Ipv4Addr::new(255, 255, 255, 254))
+        );
+        assert_eq!(addresses.size_hint(), ((1 << 31) - 3, Some((1 << 31) - 3)));
+        assert_eq!(addresses.nth(1 << 31), None);
+        assert_eq!(addresses.size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn iterate_network_hosts() {
+        let network = Network::try_from("192.168.1.0/29").unwrap();
+        let hosts: Vec<Ipv4Addr> = network.hosts().collect();
+        assert_eq!(
+            hosts,
+            (1..=6)
+                .map(|n| Ipv4Addr::new(192, 168, 1, n))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            network.hosts().size_hint().0 as u64,
+            network.number_of_hosts()
+        );
+        assert_eq!(network.hosts().next(), network.first_host_address());
+        assert_eq!(network.hosts().next_back(), network.last_host_address());
+        assert_eq!(
+            network.hosts().rev().collect::<Vec<_>>(),
+            hosts.iter().rev().copied().collect::<Vec<_>>()
+        );
+
+        let mut hosts = network.hosts();
+        assert_eq!(hosts.nth(2), Some(Ipv4Addr::new(192, 168, 1, 3)));
+        assert_eq!(hosts.nth_back(1), Some(Ipv4Addr::new(192, 168, 1, 5)));
+        assert_eq!(hosts.size_hint(), (1, Some(1)));
+        assert_eq!(hosts.next(), Some(Ipv4Addr::new(192, 168, 1, 4)));
+        assert_eq!(hosts.next(), None);
+
+        let addresses: Vec<Ipv4Addr> = network.addresses().collect();
+        assert_eq!(
+            addresses,
+            (0..=7)
+                .map(|n| Ipv4Addr::new(192, 168, 1, n))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            network.addresses().size_hint().0 as u64,
+            network.number_of_addresses()
+        );
+
+        let network = Network::try_from("192.168.1.6/31").unwrap();
+        assert_eq!(
+            network.hosts().collect::<Vec<_>>(),
+            vec![Ipv4Addr::new(192, 168, 1, 6), Ipv4Addr::new(192, 168, 1, 7)]
+        );
+        assert_eq!(
+            network.addresses().collect::<Vec<_>>(),
+            vec![Ipv4Addr::new(192, 168, 1, 6), Ipv4Addr::new(192, 168, 1, 7)]
+        );
+
+        let network = Network::try_from("192.168.1.9/32").unwrap();
+        assert_eq!(network.hosts().size_hint(), (0, Some(0)));
+        assert_eq!(network.hosts().next(), None);
+        assert_eq!(network.hosts().next_back(), None);
+        assert_eq!(
+            network.addresses().collect::<Vec<_>>(),
+            vec![Ipv4Addr::new(192, 168, 1, 9)]
+        );
+
+        let network = Network::try_from("255.255.255.0/24").unwrap();
+        assert_eq!(
+            network.hosts().last(),
+            Some(Ipv4Addr::new(255, 255, 255, 254))
+        );
+        assert_eq!(
+            network.addresses().last(),
+            Some(Ipv4Addr::new(255, 255, 255, 255))
+        );
+
+        let network = Network::try_from("10.0.0.0/8").unwrap();
+        let mut hosts = network.hosts();
+        assert_eq!(hosts.size_hint(), (16_777_214, Some(16_777_214)));
+        assert_eq!(hosts.nth(65_535), Some(Ipv4Addr::new(10, 1, 0, 0)));
+        assert_eq!(
+            hosts.nth_back(65_535),
+            Some(Ipv4Addr::new(10, 254, 255, 255))
+        );
+        assert_eq!(
+            hosts.size_hint(),
+            (16_777_214 - 2 * 65_536, Some(16_777_214 - 2 * 65_536))
+        );
+        assert_eq!(hosts.nth(usize::MAX), None);
+        assert_eq!(hosts.next_back(), None);
+    }
+
+    #[test]
+    fn random_access_slsm() {
+        let base_network = Network::try_from("10.0.0.0/16").unwrap();
+        let slsm = Slsm::new(base_network.clone(), Cidr::new(24).unwrap()).unwrap();
+        assert_eq!(slsm.subnet_count(), 256);
+        assert_eq!(slsm.size_hint(), (256, Some(256)));
+        assert_eq!(slsm.get(0), Some(Network::try_from("10.0.0.0/24").unwrap()));
+        assert_eq!(
+            slsm.get(37),
+            Some(Network::try_from("10.0.37.0/24").unwrap())
+        );
+        assert_eq!(
+            slsm.get(255),
+            Some(Network::try_from("10.0.255.0/24").unwrap())
+        );
+        assert_eq!(slsm.get(256), None);
+        assert_eq!(slsm.get(u32::MAX), None);
+        for (n, subnet) in slsm.clone().enumerate() {
+            assert_eq!(slsm.get(n as u32), Some(subnet));
+        }
+
+        assert_eq!(
+            slsm.index_of(&Network::try_from("10.0.37.0/24").unwrap()),
+            Some(37)
+        );
+        assert_eq!(
+            slsm.index_of(&Network::try_from("10.0.37.0/25").unwrap()),
+            None
+        );
+        assert_eq!(
+            slsm.index_of(&Network::try_from("10.1.37.0/24").unwrap()),
+            None
+        );
+        assert_eq!(
+            slsm.index_of_address(Ipv4Addr::new(10, 0, 37, 200)),
+            Some(37)
+        );
+        assert_eq!(
+            slsm.index_of_address(Ipv4Addr::new(10, 0, 255, 255)),
+            Some(255)
+        );
+        assert_eq!(slsm.index_of_address(Ipv4Addr::new(10, 1, 0, 0)), None);
+
+        let mut subnets = slsm.clone();
+        assert_eq!(
+            subnets.nth(36),
+            Some(Network::try_from("10.0.36.0/24").unwrap())
+        );
+        assert_eq!(subnets.size_hint(), (219, Some(219)));
+        assert_eq!(
+            subnets.next_back(),
+            Some(Network::try_from("10.0.255.0/24").unwrap())
+        );
+        assert_eq!(
+            subnets.nth_back(9),
+            Some(Network::try_from("10.0.245.0/24").unwrap())
+        );
+        assert_eq!(subnets.size_hint(), (208, Some(208)));
+        assert_eq!(
+            subnets.get(37),
+            Some(Network::try_from("10.0.37.0/24").unwrap())
+        );
+        assert_eq!(subnets.nth(usize::MAX), None);
+        assert_eq!(subnets.next_back(), None);
+        assert_eq!(subnets.size_hint(), (0, Some(0)));
+        assert_eq!(subnets.subnets().subnet_count(), 256);
+
+        let reversed: Vec<Network> = slsm.clone().rev().collect();
+        let mut forward: Vec<Network> = slsm.clone().collect();
+        forward.reverse();
+        assert_eq!(reversed, forward);
+        assert_eq!(
+            slsm.clone().last(),
+            Some(Network::try_from("10.0.255.0/24").unwrap())
+        );
+
+        let slsm = Slsm::new(base_network.clone(), Cidr::new(16).unwrap()).unwrap();
+        assert_eq!(slsm.subnet_count(), 1);
+        assert_eq!(slsm.get(0), Some(base_network));
+        assert_eq!(
+            slsm.index_of_address(Ipv4Addr::new(10, 0, 255, 255)),
+            Some(0)
+        );
+
+        let slsm = Slsm::new(
+            Network::try_from("0.0.0.0/0").unwrap(),
+            Cidr::new(0).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(slsm.get(0), Some(Network::try_from("0.0.0.0/0").unwrap()));
+        assert_eq!(
+            slsm.index_of_address(Ipv4Addr::new(255, 255, 255, 255)),
+            Some(0)
+        );
+
+        let slsm = Slsm::new(
+            Network::try_from("0.0.0.0/1").unwrap(),
+            Cidr::new(32).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(slsm.subnet_count(), 1 << 31);
+        assert_eq!(slsm.size_hint(), (1 << 31, Some(1 << 31)));
+        assert_eq!(
+            slsm.get((1 << 31) - 1),
+            Some(Network::try_from("127.255.255.255/32").unwrap())
+        );
+        assert_eq!(
+            slsm.index_of_address(Ipv4Addr::new(127, 255, 255, 255)),
+            Some((1 << 31) - 1)
+        );
+        assert_eq!(
+            slsm.clone().rev().nth(1),
+            Some(Network::try_from("127.255.255.254/32").unwrap())
+        );
+    }
+
+    #[test]
+    fn count_every_prefix_length() {
+        for cidr in 0..=32_u8 {
+            let network =
+                Network::new(Ipv4Addr::new(255, 255, 255, 255), Cidr::new(cidr).unwrap()).unwrap();
+            let addresses = 1_u64 << (32 - cidr);
+            assert_eq!(network.number_of_addresses(), addresses);
+            let hosts = match cidr {
+                32 => 0,
+                31 => 2,
+                _ => addresses - 2,
+            };
+            assert_eq!(network.number_of_hosts(), hosts);
+            assert_eq!(network.hosts().size_hint().0 as u64, hosts);
+            #[cfg(target_pointer_width = "64")]
+            assert_eq!(network.addresses().len() as u64, addresses);
+
+            let slsm = Slsm::new(
+                Network::try_from("0.0.0.0/0").unwrap(),
+                Cidr::new(cidr).unwrap(),
+            )
+            .unwrap();
+            assert_eq!(slsm.subnet_count(), 1 << cidr);
+            #[cfg(target_pointer_width = "64")]
+            assert_eq!(slsm.len() as u64, 1 << cidr);
+            assert_eq!(slsm.used_addresses(), 1 << 32);
+            assert_eq!(slsm.wasted_addresses(), 0);
+            assert_eq!(
+                slsm.clone().next(),
+                Some(Network::new(Ipv4Addr::new(0, 0, 0, 0), Cidr::new(cidr).unwrap()).unwrap())
+            );
+            assert_eq!(slsm.clone().next_back(), Some(network.clone()));
+
+            let slsm = Slsm::new(network.clone(), Cidr::new(32).unwrap()).unwrap();
+            assert_eq!(slsm.subnet_count(), addresses);
+            #[cfg(target_pointer_width = "64")]
+            assert_eq!(slsm.len() as u64, addresses);
+            assert_eq!(
+                slsm.clone().last(),
+                Some(Network::try_from("255.255.255.255/32").unwrap())
+            );
+
+            // A /30 has as many hosts as a /31, so a Vlsm allocates a /31 for them.
+            if cidr != 30 {
+                let vlsm = Vlsm::new(network.clone(), vec![hosts as u32]).unwrap();
+                assert_eq!(vlsm.subnet_count(), 1);
+                assert_eq!(vlsm.wasted_addresses(), 0);
+                assert_eq!(vlsm.collect::<Vec<_>>(), vec![network.clone()]);
+            }
+
+            if (1..=29).contains(&cidr) {
+                let vlsm = Vlsm::new(network.clone(), vec![hosts as u32 + 1]);
+                assert_eq!(
+                    vlsm,
+                    Err(NetworkError::InsufficientAddressSpace {
+                        index: 0,
+                        hosts: hosts as u32 + 1,
+                        required: addresses as u128 * 2,
+                        available: addresses as u128,
+                    })
+                );
+            }
+        }
+
+        let network = Network::try_from("0.0.0.0/0").unwrap();
+        assert_eq!(network.number_of_hosts(), 4_294_967_294);
+
+        let vlsm = Vlsm::new(network.clone(), vec![1, u32::MAX]);
+        assert_eq!(
+            vlsm,
+            Err(NetworkError::HostCountOutOfRange {
+                hosts: u32::MAX as u64
+            })
+        );
+
+        let vlsm = Vlsm::new(network, vec![2_147_483_646, 2_147_483_646]).unwrap();
+        assert_eq!(
+            vlsm.collect::<Vec<_>>(),
+            vec![
+                Network::try_from("0.0.0.0/1").unwrap(),
+                Network::try_from("128.0.0.0/1").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn cidr_for_host_and_subnet_count() {
+        let for_hosts = |hosts| Cidr::for_host_count(hosts).map(|cidr| *cidr);
+        assert_eq!(for_hosts(0), Ok(32));
+        assert_eq!(for_hosts(1), Ok(31));
+        assert_eq!(for_hosts(2), Ok(31));
+        assert_eq!(for_hosts(3), Ok(29));
+        assert_eq!(for_hosts(6), Ok(29));
+        assert_eq!(for_hosts(7), Ok(28));
+        assert_eq!(for_hosts(254), Ok(24));
+        assert_eq!(for_hosts(255), Ok(23));
+        assert_eq!(for_hosts(16_777_214), Ok(8));
+        assert_eq!(for_hosts(16_777_215), Ok(7));
+        assert_eq!(for_hosts(2_147_483_646), Ok(1));
+        assert_eq!(for_hosts(2_147_483_647), Ok(0));
+        assert_eq!(for_hosts(4_294_967_294), Ok(0));
+        assert_eq!(
+            for_hosts(4_294_967_295),
+            Err(NetworkError::HostCountOutOfRange {
+                hosts: 4_294_967_295
+            })
+        );
+
+        // The chosen Cidr is the smallest Network with enough hosts. A /30 has as many hosts as a /31.
+        for cidr in 0..=32 {
+            let network =
+                Network::new(Ipv4Addr::new(0, 0, 0, 0), Cidr::new(cidr).unwrap()).unwrap();
+            let hosts = network.number_of_hosts() as u32;
+            let cidr = Cidr::for_host_count(hosts).unwrap();
+            assert_eq!(
+                *cidr,
+                if *network.cidr() == 30 {
+                    31
+                } else {
+                    *network.cidr()
+                }
+            );
+            if hosts < u32::MAX - 1 {
+                assert!(Cidr::for_host_count(hosts + 1).unwrap() < cidr);
+            }
+        }
+
+        let for_subnets = |base, subnets| {
+            Cidr::for_subnet_count(Cidr::new(base).unwrap(), subnets).map(|cidr| *cidr)
+        };
+        assert_eq!(for_subnets(24, 0), Ok(24));
+        assert_eq!(for_subnets(24, 1), Ok(24));
+        assert_eq!(for_subnets(24, 2), Ok(25));
+        assert_eq!(for_subnets(24, 3), Ok(26));
+        assert_eq!(for_subnets(24, 4), Ok(26));
+        assert_eq!(for_subnets(24, 256), Ok(32));
+        assert_eq!(
+            for_subnets(24, 257),
+            Err(NetworkError::SubnetCountOutOfRange {
+                cidr: 24,
+                subnets: 257
+            })
+        );
+        assert_eq!(for_subnets(0, 1 << 32), Ok(32));
+        assert_eq!(
+            for_subnets(0, (1 << 32) + 1),
+            Err(NetworkError::SubnetCountOutOfRange {
+                cidr: 0,
+                subnets: (1 << 32) + 1
+            })
+        );
+        assert_eq!(
+            for_subnets(32, u64::MAX),
+            Err(NetworkError::SubnetCountOutOfRange {
+                cidr: 32,
+                subnets: u64::MAX
+            })
+        );
+
+        let base_network = Network::try_from("10.0.0.0/16").unwrap();
+        let cidr = Cidr::for_subnet_count(base_network.cidr(), 37).unwrap();
+        let slsm = Slsm::new(base_network, cidr).unwrap();
+        assert_eq!(slsm.subnet_count(), 64);
+    }
+
+    #[test]
+    fn classify_special_purpose_addresses() {
+        let classify = |s: &str| s.parse::<Ipv4Addr>().unwrap().classify();
+
+        let classification = classify("10.20.30.40");
+        assert_eq!(classification.class(), AddressClass::Private);
+        assert_eq!(classification.rfc(), Some("RFC 1918"));
+        assert_eq!(classification.name(), "Private-Use");
+        assert_eq!(
+            classification.block(),
+            Some(&Network::try_from("10.0.0.0/8").unwrap())
+        );
+
+        assert_eq!(classify("172.31.255.255").class(), AddressClass::Private);
+        assert_eq!(classify("172.32.0.0").class(), AddressClass::Global);
+        assert_eq!(classify("192.168.1.1").class(), AddressClass::Private);
+        assert_eq!(
+            classify("100.64.0.1").class(),
+            AddressClass::SharedAddressSpace
+        );
+        assert_eq!(classify("100.64.0.1").rfc(), Some("RFC 6598"));
+        assert_eq!(classify("100.128.0.0").class(), AddressClass::Global);
+        assert_eq!(classify("127.0.0.1").class(), AddressClass::Loopback);
+        assert_eq!(classify("169.254.1.1").class(), AddressClass::LinkLocal);
+        assert_eq!(classify("224.0.0.251").class(), AddressClass::Multicast);
+        assert_eq!(classify("239.255.255.250").class(), AddressClass::Multicast);
+        assert_eq!(classify("233.252.0.1").class(), AddressClass::Documentation);
+        assert_eq!(classify("233.252.0.1").rfc(), Some("RFC 6676"));
+        assert_eq!(classify("192.0.2.1").class(), AddressClass::Documentation);
+        assert_eq!(
+            classify("198.51.100.1").class(),
+            AddressClass::Documentation
+        );
+        assert_eq!(classify("203.0.113.1").class(), AddressClass::Documentation);
+        assert_eq!(
+            classify("198.19.255.255").class(),
+            AddressClass::Benchmarking
+        );
+        assert_eq!(classify("0.1.2.3").class(), AddressClass::Reserved);
+        assert_eq!(classify("0.0.0.0").name(), "This host on this network");
+        assert_eq!(classify("240.0.0.1").class(), AddressClass::Reserved);
+        assert_eq!(classify("255.255.255.255").name(), "Limited Broadcast");
+        assert_eq!(classify("192.0.0.8").class(), AddressClass::Reserved);
+        assert_eq!(classify("192.0.0.9").class(), AddressClass::Global);
+        assert_eq!(classify("192.0.0.9").rfc(), Some("RFC 7723"));
+        assert_eq!(classify("192.175.48.1").class(), AddressClass::Global);
+        assert_eq!(classify("192.88.99.1").class(), AddressClass::Reserved);
+        assert_eq!(classify("192.88.99.2").class(), AddressClass::Global);
+        assert_eq!(classify("192.88.99.2").name(), "6a44-relay anycast address");
+        assert_eq!(classify("192.88.99.2").rfc(), Some("RFC 6751"));
+
+        let classification = classify("8.8.8.8");
+        assert_eq!(classification.class(), AddressClass::Global);
+        assert_eq!(classification.block(), None);
+        assert_eq!(classification.rfc(), None);
+
+        let network = Network::try_from("192.168.4.0/22").unwrap();
+        assert_eq!(network.address_class(), Some(AddressClass::Private));
+        let parts = network.classify();
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].0, network);
+        assert_eq!(parts[0].1.class(), AddressClass::Private);
+
+        let network = Network::try_from("0.0.0.0/8").unwrap();
+        assert_eq!(network.address_class(), Some(AddressClass::Reserved));
+        assert_eq!(network.classify().len(), 25);
+
+        // 172.0.0.0/8 straddles the 172.16.0.0/12 private block.
+        let network = Network::try_from("172.0.0.0/8").unwrap();
+        assert_eq!(network.address_class(), None);
+        let parts: Vec<(String, AddressClass)> = network
+            .classify()
+            .into_iter()
+            .map(|(network, classification)| (network.to_string(), classification.class()))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                ("172.0.0.0/12".to_string(), AddressClass::Global),
+                ("172.16.0.0/12".to_string(), AddressClass::Private),
+                ("172.32.0.0/11".to_string(), AddressClass::Global),
+                ("172.64.0.0/10".to_string(), AddressClass::Global),
+                ("172.128.0.0/9".to_string(), AddressClass::Global),
+            ]
+        );
+
+        let network = Network::try_from("192.0.0.0/28").unwrap();
+        let parts: Vec<(String, &str)> = network
+            .classify()
+            .into_iter()
+            .map(|(network, classification)| (network.to_string(), classification.name()))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                ("192.0.0.0/29".to_string(), "IPv4 Service Continuity Prefix"),
+                ("192.0.0.8/32".to_string(), "IPv4 dummy address"),
+                ("192.0.0.9/32".to_string(), "Port Control Protocol Anycast"),
+                (
+                    "192.0.0.10/32".to_string(),
+                    "Traversal Using Relays around NAT Anycast"
+                ),
+                ("192.0.0.11/32".to_string(), "IETF Protocol Assignments"),
+                ("192.0.0.12/30".to_string(), "IETF Protocol Assignments"),
+            ]
+        );
+
+        // Every address of the whole address space is classified exactly once, in order.
+        let parts = Network::try_from("0.0.0.0/0").unwrap().classify();
+        let set: IpSet = parts.iter().map(|(network, _)| network.clone()).collect();
+        assert_eq!(set.len(), 1 << 32);
+        assert_eq!(
+            parts
+                .iter()
+                .map(|(network, _)| network.number_of_addresses())
+                .sum::<u64>(),
+            1 << 32
+        );
+        assert!(parts.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        for (network, classification) in parts {
+            assert_eq!(network.network_id().classify(), classification);
+            assert_eq!(
+                Ipv4Addr::from(network.last_address_u32()).classify(),
+                classification
+            );
+        }
+    }
+
+    #[test]
+    fn allocate_from_pool() {
+        let network = |s: &str| Network::try_from(s).unwrap();
+        let cidr = |n: u8| Cidr::new(n).unwrap();
+
+        let mut pool = Pool::new(network("10.0.0.0/24"));
+        assert_eq!(pool.free_addresses(), 256);
+        assert_eq!(pool.largest_free_block(), Some(network("10.0.0.0/24")));
+
+        assert_eq!(pool.allocate(cidr(26)), Ok(network("10.0.0.0/26")));
+        assert_eq!(pool.allocate_hosts(20), Ok(network("10.0.0.64/27")));
+        assert_eq!(pool.allocate(cidr(30)), Ok(network("10.0.0.96/30")));
+        assert_eq!(pool.allocate_hosts(2), Ok(network("10.0.0.100/31")));
+        assert!(pool.is_allocated(&network("10.0.0.64/27")));
+        assert_eq!(pool.free_addresses(), 256 - 64 - 32 - 4 - 2);
+        assert_eq!(
+            pool.free_networks().collect::<Vec<_>>(),
+            vec![
+                network("10.0.0.102/31"),
+                network("10.0.0.104/29"),
+                network("10.0.0.112/28"),
+                network("10.0.0.128/25"),
+            ]
+        );
+        assert_eq!(pool.largest_free_block(), Some(network("10.0.0.128/25")));
+
+        // Smaller allocations are taken from the smallest free block they fit in.
+        assert_eq!(pool.allocate(cidr(29)), Ok(network("10.0.0.104/29")));
+        assert_eq!(pool.allocate(cidr(27)), Ok(network("10.0.0.128/27")));
+
+        assert_eq!(pool.allocate_specific(network("10.0.0.192/26")), Ok(()));
+        assert_eq!(
+            pool.allocate_specific(network("10.0.0.96/27")),
+            Err(NetworkError::AllocationConflict {
+                network: network("10.0.0.96/27"),
+                allocated: network("10.0.0.96/30"),
+            })
+        );
+        assert_eq!(
+            pool.allocate_specific(network("10.0.0.0/24")),
+            Err(NetworkError::AllocationConflict {
+                network: network("10.0.0.0/24"),
+                allocated: network("10.0.0.0/26"),
+            })
+        );
+        assert_eq!(
+            pool.allocate_specific(network("10.0.0.8/29")),
+            Err(NetworkError::AllocationConflict {
+                network: network("10.0.0.8/29"),
+                allocated: network("10.0.0.0/26"),
+            })
+        );
+        assert_eq!(
+            pool.allocate_specific(network("10.0.1.0/29")),
+            Err(NetworkError::NetworkOutsideBase {
+                network: network("10.0.1.0/29")
+            })
+        );
+        assert_eq!(
+            pool.allocate(cidr(23)),
+            Err(NetworkError::InvalidSubnetCidr)
+        );
+        assert_eq!(
+            pool.allocate(cidr(26)),
+            Err(NetworkError::PoolExhausted { cidr: 26 })
+        );
+        assert_eq!(
+            pool.allocate_hosts(u32::MAX),
+            Err(NetworkError::HostCountOutOfRange {
+                hosts: u32::MAX as u64
+            })
+        );
+
+        assert_eq!(
+            pool.allocated().cloned().collect::<Vec<_>>(),
+            vec![
+                network("10.0.0.0/26"),
+                network("10.0.0.64/27"),
+                network("10.0.0.96/30"),
+                network("10.0.0.100/31"),
+                network("10.0.0.104/29"),
+                network("10.0.0.128/27"),
+                network("10.0.0.192/26"),
+            ]
+        );
+        assert_eq!(pool.subnet_count(), 7);
+        assert_eq!(pool.used_addresses(), 206);
+        assert_eq!(pool.wasted_addresses(), 50);
+
+        assert_eq!(pool.release(&network("10.0.0.128/27")), Ok(()));
+        assert_eq!(
+            pool.release(&network("10.0.0.128/27")),
+            Err(NetworkError::NotAllocated {
+                network: network("10.0.0.128/27")
+            })
+        );
+        assert_eq!(
+            pool.release(&network("10.0.0.0/27")),
+            Err(NetworkError::NotAllocated {
+                network: network("10.0.0.0/27")
+            })
+        );
+        assert_eq!(pool.largest_free_block(), Some(network("10.0.0.128/26")));
+        assert_eq!(pool.allocate(cidr(26)), Ok(network("10.0.0.128/26")));
+
+        for allocated in pool.allocated().cloned().collect::<Vec<_>>() {
+            pool.release(&allocated).unwrap();
+        }
+        assert_eq!(pool, Pool::new(network("10.0.0.0/24")));
+
+        let mut pool = Pool::new(network("0.0.0.0/0"));
+        assert_eq!(pool.allocate(cidr(0)), Ok(network("0.0.0.0/0")));
+        assert_eq!(pool.free_addresses(), 0);
+        assert_eq!(pool.largest_free_block(), None);
+        assert_eq!(
+            pool.allocate(cidr(32)),
+            Err(NetworkError::PoolExhausted { cidr: 32 })
+        );
+    }
+
+    /// Check that Ledgers opened with open in separate threads, all sharing one storage, never allocate the
+    /// same network.
+    #[cfg(feature = "ledger")]
+    fn check_concurrent_ledgers<S: crate::ledger::Storage>(
+        first: &crate::ledger::Ledger<S>,
+        open: impl Fn() -> crate::ledger::Ledger<S> + Sync,
+    ) {
+        let base_network = Network::try_from("172.16.0.0/16").unwrap();
+        let mut allocated: Vec<Network> = std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut ledger = open();
+                        (0..8)
+                            .map(|_| {
+                                ledger
+                                    .allocate(&base_network, Cidr::new(24).unwrap())
+                                    .unwrap()
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            threads
+                .into_iter()
+                .flat_map(|thread| thread.join().unwrap())
+                .collect()
+        });
+        allocated.sort();
+        allocated.dedup();
+        assert_eq!(allocated.len(), 64);
+        assert_eq!(first.allocated(&base_network).unwrap(), allocated);
+    }
+
+    /// Check that allocations made through one Ledger are seen by a second Ledger sharing its storage.
+    #[cfg(feature = "ledger")]
+    fn check_shared_ledgers<S: crate::ledger::Storage>(
+        first: &mut crate::ledger::Ledger<S>,
+        second: &mut crate::ledger::Ledger<S>,
+    ) {
+        use crate::ledger::LedgerError;
+
+        let network = |s: &str| Network::try_from(s).unwrap();
+        let base_network = network("10.0.0.0/16");
+        let slsm = Slsm::new(base_network.clone(), Cidr::new(24).unwrap()).unwrap();
+
+        assert_eq!(first.allocated(&base_network).unwrap(), vec![]);
+        assert_eq!(
+            first.allocate_slsm(&slsm, 2).unwrap(),
+            vec![network("10.0.0.0/24"), network("10.0.1.0/24")]
+        );
+        assert_eq!(
+            second.allocate_slsm(&slsm, 2).unwrap(),
+            vec![network("10.0.2.0/24"), network("10.0.3.0/24")]
+        );
+        second
+            .allocate_specific(&base_network, network("10.0.5.0/24"))
+            .unwrap();
+        assert_eq!(
+            first.allocate_slsm(&slsm, 2).unwrap(),
+            vec![network("10.0.4.0/24"), network("10.0.6.0/24")]
+        );
+
+        let vlsm = Vlsm::new(base_network.clone(), vec![100, 500]).unwrap();
+        assert_eq!(
+            second.allocate_vlsm(&vlsm).unwrap(),
+            vec![network("10.0.8.0/23"), network("10.0.7.0/25")]
+        );
+        assert_eq!(
+            first
+                .allocate(&base_network, Cidr::new(26).unwrap())
+                .unwrap(),
+            network("10.0.7.128/26")
+        );
+        assert_eq!(
+            first.allocate_hosts(&base_network, 10).unwrap(),
+            network("10.0.7.192/28")
+        );
+
+        // A failed allocation records nothing.
+        let before = second.allocated(&base_network).unwrap();
+        assert_eq!(before.len(), 11);
+        assert!(matches!(
+            second.allocate_slsm(&slsm, 250),
+            Err(LedgerError::Network(NetworkError::PoolExhausted {
+                cidr: 24
+            }))
+        ));
+        let vlsm = Vlsm::new(base_network.clone(), vec![30000, 30000]).unwrap();
+        assert!(matches!(
+            second.allocate_vlsm(&vlsm),
+            Err(LedgerError::Network(
+                NetworkError::InsufficientAddressSpace {
+                    index: 1,
+                    hosts: 30000,
+                    ..
+                }
+            ))
+        ));
+        let vlsm = Vlsm::with_requirements(
+            base_network.clone(),
+            vec![Requirement::pinned("taken", network("10.0.0.0/24"))],
+        )
+        .unwrap();
+        assert!(matches!(
+            second.allocate_vlsm(&vlsm),
+            Err(LedgerError::Network(
+                NetworkError::AllocationConflict { .. }
+            ))
+        ));
+        assert!(matches!(
+            second.allocate_specific(&base_network, network("10.0.0.128/25")),
+            Err(LedgerError::Network(
+                NetworkError::AllocationConflict { .. }
+            ))
+        ));
+        assert_eq!(first.allocated(&base_network).unwrap(), before);
+
+        first
+            .release(&base_network, &network("10.0.1.0/24"))
+            .unwrap();
+        assert!(matches!(
+            second.release(&base_network, &network("10.0.1.0/24")),
+            Err(LedgerError::Network(NetworkError::NotAllocated { .. }))
+        ));
+        assert_eq!(
+            second.allocate_slsm(&slsm, 1).unwrap(),
+            vec![network("10.0.1.0/24")]
+        );
+
+        // Base networks are recorded separately.
+        let other_base_network = network("10.0.0.0/24");
+        assert_eq!(
+            second
+                .allocate(&other_base_network, Cidr::new(25).unwrap())
+                .unwrap(),
+            network("10.0.0.0/25")
+        );
+        assert_eq!(
+            first.pool(&other_base_network).unwrap().free_addresses(),
+            128
+        );
+        assert_eq!(first.allocated(&base_network).unwrap().len(), 11);
+    }
+
+    #[cfg(feature = "ledger")]
+    #[test]
+    fn record_allocations_in_json_ledger() {
+        use crate::json_file_storage::JsonFileStorage;
+        use crate::ledger::{Ledger, LedgerError, MemoryStorage};
+
+        let mut ledger = Ledger::new(MemoryStorage::new());
+        let base_network = Network::try_from("192.168.0.0/24").unwrap();
+        ledger.allocate_hosts(&base_network, 50).unwrap();
+        let mut copy = ledger.clone();
+        assert_eq!(
+            copy.allocate_hosts(&base_network, 50).unwrap(),
+            Network::try_from("192.168.0.64/26").unwrap()
+        );
+        assert_eq!(ledger.allocated(&base_network).unwrap().len(), 1);
+        let vlsm = Vlsm::new(base_network.clone(), vec![20]).unwrap();
+        assert_eq!(
+            copy.allocate_vlsm_with_placement(&vlsm, &TopDown).unwrap(),
+            vec![Network::try_from("192.168.0.224/27").unwrap()]
+        );
+        let vlsm = Vlsm::with_requirements(
+            base_network.clone(),
+            vec![Requirement::hosts("servers", 10).with_buddy_reserved()],
+        )
+        .unwrap();
+        assert_eq!(
+            copy.allocate_vlsm(&vlsm).unwrap(),
+            vec![Network::try_from("192.168.0.128/28").unwrap()]
+        );
+        assert!(copy
+            .allocated(&base_network)
+            .unwrap()
+            .contains(&Network::try_from("192.168.0.144/28").unwrap()));
+
+        let directory =
+            std::env::temp_dir().join(format!("subnet-json-ledger-test-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("ledger.json");
+
+        let mut first = Ledger::new(JsonFileStorage::new(&path));
+        let mut second = Ledger::new(JsonFileStorage::new(&path));
+        check_shared_ledgers(&mut first, &mut second);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(json["10.0.0.0/24"], serde_json::json!(["10.0.0.0/25"]));
+        assert_eq!(json["10.0.0.0/16"][0], "10.0.0.0/24");
+        assert!(!directory.join("ledger.json.tmp").exists());
+
+        check_concurrent_ledgers(&first, || Ledger::new(JsonFileStorage::new(path.clone())));
+
+        std::fs::write(&path, "{\"10.0.0.0/16\": [\"10.0.0.0/33\"]}").unwrap();
+        assert!(matches!(
+            first.allocated(&Network::try_from("10.0.0.0/16").unwrap()),
+            Err(LedgerError::Json(_))
+        ));
+        std::fs::write(&path, "{\"10.0.0.0/16\": [\"10.1.0.0/24\"]}").unwrap();
+        assert!(matches!(
+            first.allocated(&Network::try_from("10.0.0.0/16").unwrap()),
+            Err(LedgerError::Network(
+                NetworkError::NetworkOutsideBase { .. }
+            ))
+        ));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn record_allocations_in_sqlite_ledger() {
+        use crate::ledger::{Ledger, LedgerError};
+        use crate::sqlite_storage::SqliteStorage;
+
+        let mut first = Ledger::new(SqliteStorage::open_in_memory().unwrap());
+        let base_network = Network::try_from("192.168.0.0/24").unwrap();
+        first.allocate_hosts(&base_network, 50).unwrap();
+        assert_eq!(first.allocated(&base_network).unwrap().len(), 1);
+
+        let directory =
+            std::env::temp_dir().join(format!("subnet-sqlite-ledger-test-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("ledger.sqlite");
+
+        let mut first = Ledger::new(SqliteStorage::open(&path).unwrap());
+        let mut second = Ledger::new(SqliteStorage::open(&path).unwrap());
+        check_shared_ledgers(&mut first, &mut second);
+        drop(first);
+        let first = Ledger::new(SqliteStorage::open(&path).unwrap());
+        assert_eq!(
+            first
+                .allocated(&Network::try_from("10.0.0.0/24").unwrap())
+                .unwrap(),
+            vec![Network::try_from("10.0.0.0/25").unwrap()]
+        );
+
+        check_concurrent_ledgers(&first, || Ledger::new(SqliteStorage::open(&path).unwrap()));
+
+        let connection = rusqlite::Connection::open(&path).unwrap();
+        connection
+            .execute(
+                "INSERT INTO allocations (base_network, network) VALUES ('172.16.0.0/16', 'bad')",
+                [],
+            )
+            .unwrap();
+        assert!(matches!(
+            first.allocated(&Network::try_from("172.16.0.0/16").unwrap()),
+            Err(LedgerError::InvalidRecord(record)) if record == "bad"
+        ));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn report_vlsm_free_space() {
+        let network = |s: &str| Network::try_from(s).unwrap();
+
+        let vlsm = Vlsm::new(network("10.0.0.0/24"), vec![60, 10, 2]).unwrap();
+        let report = vlsm.report();
+        assert_eq!(report.base_network(), &network("10.0.0.0/24"));
+        assert_eq!(
+            report.allocated(),
+            &[
+                network("10.0.0.0/26"),
+                network("10.0.0.64/28"),
+                network("10.0.0.80/31"),
+            ]
+        );
+        assert_eq!(
+            report.free(),
+            &[
+                network("10.0.0.82/31"),
+                network("10.0.0.84/30"),
+                network("10.0.0.88/29"),
+                network("10.0.0.96/27"),
+                network("10.0.0.128/25"),
+            ]
+        );
+        assert_eq!(report.allocated_addresses(), 82);
+        assert_eq!(report.free_addresses(), 174);
+        assert_eq!(report.allocated_addresses(), vlsm.used_addresses());
+        assert_eq!(report.free_addresses(), vlsm.wasted_addresses());
+        assert_eq!(report.rounding_waste(), 2 + 4);
+        assert!((report.utilization() - 32.03125).abs() < 1e-9);
+
+        // The report does not depend on how far the Vlsm has been iterated.
+        let mut iterated = vlsm.clone();
+        iterated.next();
+        assert_eq!(iterated.report(), report);
+
+        let vlsm = Vlsm::new(
+            network("192.168.0.0/24"),
+            vec![126, 62, 30, 14, 6, 2, 2, 2, 0, 0],
+        )
+        .unwrap();
+        let report = vlsm.report();
+        assert_eq!(report.free(), &[] as &[Network]);
+        assert_eq!(report.rounding_waste(), 0);
+        assert_eq!(report.utilization(), 100.0);
+
+        let vlsm = Vlsm::new(network("192.168.0.0/24"), vec![]).unwrap();
+        let report = vlsm.report();
+        assert_eq!(report.allocated(), &[] as &[Network]);
+        assert_eq!(report.free(), &[network("192.168.0.0/24")]);
+        assert_eq!(report.utilization(), 0.0);
+    }
+
+    #[test]
+    fn labelled_vlsm_requirements() {
+        let network = |s: &str| Network::try_from(s).unwrap();
+
+        let requirements = vec![
+            Requirement::hosts("vlan-10 users", 50).with_metadata("vlan", "10"),
+            Requirement::hosts("wan link", 2),
+            Requirement::hosts("vlan-20 servers", 100)
+                .with_metadata("vlan", "20")
+                .with_metadata("site", "dc1"),
+            Requirement::hosts("vlan-30 voice", 60),
+        ];
+        let vlsm = Vlsm::with_requirements(network("10.0.0.0/23"), requirements.clone()).unwrap();
+        assert_eq!(vlsm.requirements(), &requirements[..]);
+        assert_eq!(vlsm.required_hosts(), &[100, 60, 50, 2]);
+
+        let labelled: Vec<(&str, &Network)> = vlsm
+            .allocations()
+            .iter()
+            .map(|allocation| (allocation.label(), allocation.network()))
+            .collect();
+        assert_eq!(
+            labelled,
+            vec![
+                ("vlan-20 servers", &network("10.0.0.0/25")),
+                ("vlan-30 voice", &network("10.0.0.128/26")),
+                ("vlan-10 users", &network("10.0.0.192/26")),
+                ("wan link", &network("10.0.1.0/31")),
+            ]
+        );
+
+        let labelled: Vec<(usize, &str, &Network)> = vlsm
+            .allocations_in_input_order()
+            .into_iter()
+            .map(|allocation| (allocation.index(), allocation.label(), allocation.network()))
+            .collect();
+        assert_eq!(
+            labelled,
+            vec![
+                (0, "vlan-10 users", &network("10.0.0.192/26")),
+                (1, "wan link", &network("10.0.1.0/31")),
+                (2, "vlan-20 servers", &network("10.0.0.0/25")),
+                (3, "vlan-30 voice", &network("10.0.0.128/26")),
+            ]
+        );
+
+        let servers = &vlsm.allocations()[0];
+        assert_eq!(servers.requirement(), &requirements[2]);
+        assert_eq!(
+            servers
+                .requirement()
+                .metadata()
+                .get("site")
+                .map(String::as_str),
+            Some("dc1")
+        );
+        assert_eq!(servers.requirement().host_count(), Some(100));
+
+        // The iterator returns the allocated subnets in allocation order.
+        assert_eq!(
+            vlsm.clone().collect::<Vec<_>>(),
+            vlsm.allocations()
+                .iter()
+                .map(|allocation| allocation.network().clone())
+                .collect::<Vec<_>>()
+        );
+
+        // Requirements with the same number of hosts keep their input order.
+        let vlsm = Vlsm::with_requirements(
+            network("10.0.0.0/24"),
+            vec![
+                Requirement::hosts("b", 10),
+                Requirement::hosts("a", 10),
+                Requirement::hosts("c", 12),
+            ],
+        )
+        .unwrap();
+        let labels: Vec<&str> = vlsm
+            .allocations()
+            .iter()
+            .map(|allocation| allocation.label())
+            .collect();
+        assert_eq!(labels, vec!["c", "b", "a"]);
+
+        let vlsm = Vlsm::with_requirements(
+            network("10.0.0.0/24"),
+            vec![
+                Requirement::hosts("small", 10),
+                Requirement::hosts("large", 200),
+                Requirement::hosts("medium", 100),
+            ],
+        );
+        assert_eq!(
+            vlsm,
+            Err(NetworkError::InsufficientAddressSpace {
+                index: 2,
+                hosts: 100,
+                required: 400,
+                available: 256,
+            })
+        );
+
+        let vlsm = Vlsm::new(network("10.0.0.0/24"), vec![10, 20]).unwrap();
+        assert_eq!(
+            vlsm.requirements(),
+            &[Requirement::hosts("", 10), Requirement::hosts("", 20)]
+        );
+        assert_eq!(vlsm.allocations_in_input_order()[0].index(), 0);
+    }
+
+    #[test]
+    fn mixed_vlsm_requirements() {
+        let network = |s: &str| Network::try_from(s).unwrap();
+
+        let requirements = vec![
+            Requirement::hosts("users", 100),
+            Requirement::cidr("link", Cidr::new(30).unwrap()),
+            Requirement::pinned("gateway", network("10.0.0.64/26")),
+            Requirement::hosts("servers", 20),
+        ];
+        let vlsm = Vlsm::with_requirements(network("10.0.0.0/24"), requirements.clone()).unwrap();
+        assert_eq!(
+            requirements[1].kind(),
+            &RequirementKind::Cidr(Cidr::new(30).unwrap())
+        );
+        assert_eq!(requirements[1].host_count(), None);
+        assert_eq!(vlsm.required_hosts(), &[62, 100, 20, 2]);
+
+        // The pinned network is reserved first and the others are packed around it, largest first.
+        let labelled: Vec<(&str, &Network)> = vlsm
+            .allocations()
+            .iter()
+            .map(|allocation| (allocation.label(), allocation.network()))
+            .collect();
+        assert_eq!(
+            labelled,
+            vec![
+                ("gateway", &network("10.0.0.64/26")),
+                ("users", &network("10.0.0.128/25")),
+                ("servers", &network("10.0.0.0/27")),
+                ("link", &network("10.0.0.32/30")),
+            ]
+        );
+
+        // Only requirements for a number of hosts are rounded up.
+        let report = vlsm.report();
+        assert_eq!(report.rounding_waste(), 36);
+        assert_eq!(
+            report.free(),
+            &[
+                network("10.0.0.36/30"),
+                network("10.0.0.40/29"),
+                network("10.0.0.48/28")
+            ]
+        );
+
+        assert_eq!(
+            Vlsm::with_requirements(
+                network("10.0.0.0/24"),
+                vec![Requirement::pinned("elsewhere", network("10.0.1.0/28"))],
+            ),
+            Err(NetworkError::NetworkOutsideBase {
+                network: network("10.0.1.0/28")
+            })
+        );
+        assert_eq!(
+            Vlsm::with_requirements(
+                network("10.0.0.0/24"),
+                vec![
+                    Requirement::pinned("gateway", network("10.0.0.64/26")),
+                    Requirement::pinned("users", network("10.0.0.0/25")),
+                ],
+            ),
+            Err(NetworkError::AllocationConflict {
+                network: network("10.0.0.0/25"),
+                allocated: network("10.0.0.64/26"),
+            })
+        );
+
+        // The pinned networks leave enough addresses, but no free block large enough.
+        assert_eq!(
+            Vlsm::with_requirements(
+                network("10.0.0.0/24"),
+                vec![
+                    Requirement::pinned("gateway", network("10.0.0.64/26")),
+                    Requirement::pinned("servers", network("10.0.0.128/26")),
+                    Requirement::hosts("users", 100),
+                ],
+            ),
+            Err(NetworkError::FragmentedAddressSpace {
+                index: 2,
+                hosts: 100,
+                cidr: 25,
+            })
+        );
+        assert_eq!(
+            NetworkError::FragmentedAddressSpace {
+                index: 2,
+                hosts: 100,
+                cidr: 25,
+            }
+            .to_string(),
+            "requirement 2 for 100 hosts does not fit: no contiguous free block of /25"
+        );
+        // The block needed includes the reserved buddy.
+        assert_eq!(
+            Vlsm::with_requirements(
+                network("10.0.0.0/24"),
+                vec![
+                    Requirement::pinned("gateway", network("10.0.0.64/26")),
+                    Requirement::pinned("voice", network("10.0.0.192/26")),
+                    Requirement::hosts("servers", 50).with_buddy_reserved(),
+                ],
+            ),
+            Err(NetworkError::FragmentedAddressSpace {
+                index: 2,
+                hosts: 50,
+                cidr: 25,
+            })
+        );
+        assert_eq!(
+            Vlsm::with_requirements(
+                network("10.0.0.0/24"),
+                vec![Requirement::cidr("too large", Cidr::new(23).unwrap())],
+            ),
+            Err(NetworkError::InsufficientAddressSpace {
+                index: 0,
+                hosts: 510,
+                required: 512,
+                available: 256,
+            })
+        );
+    }
+
+    #[test]
+    fn vlsm_placement_strategies() {
+        let network = |s: &str| Network::try_from(s).unwrap();
+        let requirements = vec![
+            Requirement::pinned("gateway", network("10.0.0.64/27")),
+            Requirement::hosts("users", 20),
+            Requirement::hosts("servers", 10),
+        ];
+        let subnets = |placement: &dyn Placement| {
+            let vlsm =
+                Vlsm::with_placement(network("10.0.0.0/24"), requirements.clone(), placement)
+                    .unwrap();
+            let report = vlsm.report();
+            (
+                vlsm.collect::<Vec<_>>(),
+                report.free().to_vec(),
+                report.largest_free_block().cloned(),
+            )
+        };
+
+        assert_eq!(
+            subnets(&FirstFit),
+            (
+                vec![
+                    network("10.0.0.64/27"),
+                    network("10.0.0.0/27"),
+                    network("10.0.0.32/28")
+                ],
+                vec![
+                    network("10.0.0.48/28"),
+                    network("10.0.0.96/27"),
+                    network("10.0.0.128/25")
+                ],
+                Some(network("10.0.0.128/25"))
+            )
+        );
+        assert_eq!(
+            Vlsm::with_requirements(network("10.0.0.0/24"), requirements.clone()),
+            Vlsm::with_placement(network("10.0.0.0/24"), requirements.clone(), &FirstFit)
+        );
+
+        // Best-fit fills the gap after the pinned network before splitting the larger blocks.
+        assert_eq!(
+            subnets(&BestFit),
+            (
+                vec![
+                    network("10.0.0.64/27"),
+                    network("10.0.0.96/27"),
+                    network("10.0.0.0/28")
+                ],
+                vec![
+                    network("10.0.0.16/28"),
+                    network("10.0.0.32/27"),
+                    network("10.0.0.128/25")
+                ],
+                Some(network("10.0.0.128/25"))
+            )
+        );
+
+        // Top-down allocates from the end of the base network, keeping the low addresses free.
+        assert_eq!(
+            subnets(&TopDown),
+            (
+                vec![
+                    network("10.0.0.64/27"),
+                    network("10.0.0.224/27"),
+                    network("10.0.0.208/28")
+                ],
+                vec![
+                    network("10.0.0.0/26"),
+                    network("10.0.0.96/27"),
+                    network("10.0.0.128/26"),
+                    network("10.0.0.192/28")
+                ],
+                Some(network("10.0.0.0/26"))
+            )
+        );
+        assert_eq!(
+            Vlsm::with_placement(network("10.0.0.0/24"), vec![], &TopDown)
+                .unwrap()
+                .report()
+                .largest_free_block(),
+            Some(&network("10.0.0.0/24"))
+        );
+
+        // Without pinned networks every strategy packs the subnets without gaps.
+        for placement in [&FirstFit as &dyn Placement, &BestFit, &TopDown] {
+            let vlsm = Vlsm::with_placement(
+                network("10.0.0.0/24"),
+                vec![
+                    Requirement::hosts("a", 100),
+                    Requirement::hosts("b", 50),
+                    Requirement::hosts("c", 50),
+                ],
+                placement,
+            )
+            .unwrap();
+            assert_eq!(vlsm.report().free_addresses(), 0);
+        }
+        assert_eq!(TopDown.place(&IpSet::new(), Cidr::new(24).unwrap()), None);
+    }
+
+    #[test]
+    fn vlsm_growth_and_buddy_reservation() {
+        let network = |s: &str| Network::try_from(s).unwrap();
+
+        assert_eq!(
+            network("10.0.0.0/25").buddy(),
+            Some(network("10.0.0.128/25"))
+        );
+        assert_eq!(
+            network("10.0.0.128/25").buddy(),
+            Some(network("10.0.0.0/25"))
+        );
+        assert_eq!(network("1.2.3.5/32").buddy(), Some(network("1.2.3.4/32")));
+        assert_eq!(network("0.0.0.0/0").buddy(), None);
+
+        let requirements = vec![
+            Requirement::hosts("servers", 10).with_buddy_reserved(),
+            Requirement::hosts("voice", 20).with_growth(Growth::Percent(50)),
+            Requirement::hosts("users", 40)
+                .with_growth(Growth::Factor(NonZeroU32::new(2).unwrap())),
+        ];
+        assert_eq!(requirements[0].planned_host_count(), Some(10));
+        assert_eq!(requirements[1].planned_host_count(), Some(30));
+        assert_eq!(requirements[2].planned_host_count(), Some(80));
+        assert_eq!(
+            Requirement::hosts("", 25)
+                .with_growth(Growth::Percent(10))
+                .planned_host_count(),
+            Some(28)
+        );
+        assert_eq!(
+            Requirement::hosts("", 300)
+                .with_growth(Growth::Factor(NonZeroU32::MIN))
+                .planned_host_count(),
+            Some(300)
+        );
+        assert_eq!(
+            Requirement::cidr("", Cidr::new(30).unwrap())
+                .with_growth(Growth::Factor(NonZeroU32::new(2).unwrap()))
+                .planned_host_count(),
+            None
+        );
+
+        let vlsm = Vlsm::with_requirements(network("10.0.0.0/24"), requirements).unwrap();
+        assert_eq!(vlsm.required_hosts(), &[80, 30, 10]);
+        let allocations: Vec<(&str, &Network, Option<&Network>)> = vlsm
+            .allocations()
+            .iter()
+            .map(|allocation| {
+                (
+                    allocation.label(),
+                    allocation.network(),
+                    allocation.reserved_buddy(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            allocations,
+            vec![
+                ("users", &network("10.0.0.0/25"), None),
+                ("voice", &network("10.0.0.128/27"), None),
+                (
+                    "servers",
+                    &network("10.0.0.160/28"),
+                    Some(&network("10.0.0.176/28"))
+                ),
+            ]
+        );
+
+        // The subnet can be widened into its buddy in place.
+        let servers = &vlsm.allocations()[2];
+        assert!(network("10.0.0.160/27").contains_network(servers.network()));
+        assert!(network("10.0.0.160/27").contains_network(servers.reserved_buddy().unwrap()));
+
+        let report = vlsm.report();
+        assert_eq!(report.reserved(), &[network("10.0.0.176/28")]);
+        assert_eq!(report.reserved_addresses(), 16);
+        assert_eq!(report.allocated_addresses(), 176);
+        assert_eq!(report.free(), &[network("10.0.0.192/26")]);
+        assert_eq!(report.free_addresses(), 64);
+        // Growth is planned for, so only the rounding beyond it is wasted.
+        assert_eq!(report.rounding_waste(), 50);
+        assert_eq!(vlsm.used_addresses(), 176);
+        assert_eq!(vlsm.wasted_addresses(), report.free_addresses());
+
+        let vlsm = Vlsm::with_placement(
+            network("10.0.0.0/24"),
+            vec![Requirement::hosts("servers", 10).with_buddy_reserved()],
+            &TopDown,
+        )
+        .unwrap();
+        assert_eq!(
+            vlsm.allocations()[0].reserved_buddy(),
+            Some(&network("10.0.0.240/28"))
+        );
+        assert_eq!(vlsm.collect::<Vec<_>>(), vec![network("10.0.0.224/28")]);
+
+        let vlsm = Vlsm::with_requirements(
+            network("10.0.0.0/24"),
+            vec![
+                Requirement::hosts("users", 100),
+                Requirement::pinned("gateway", network("10.0.0.64/26")).with_buddy_reserved(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            vlsm.allocations()[0].reserved_buddy(),
+            Some(&network("10.0.0.0/26"))
+        );
+        assert_eq!(
+            vlsm.collect::<Vec<_>>(),
+            vec![network("10.0.0.64/26"), network("10.0.0.128/25")]
+        );
+
+        assert_eq!(
+            Vlsm::with_requirements(
+                network("10.0.0.0/24"),
+                vec![
+                    Requirement::pinned("gateway", network("10.0.0.0/26")).with_buddy_reserved(),
+                    Requirement::pinned("servers", network("10.0.0.64/27")),
+                ],
+            ),
+            Err(NetworkError::AllocationConflict {
+                network: network("10.0.0.64/27"),
+                allocated: network("10.0.0.64/26"),
+            })
+        );
+        assert_eq!(
+            Vlsm::with_requirements(
+                network("10.0.0.0/24"),
+                vec![Requirement::pinned("all", network("10.0.0.0/24")).with_buddy_reserved()],
+            ),
+            Err(NetworkError::NetworkOutsideBase {
+                network: network("10.0.1.0/24")
+            })
+        );
+        assert_eq!(
+            Vlsm::with_requirements(
+                network("10.0.0.0/24"),
+                vec![
+                    Requirement::hosts("users", 100).with_buddy_reserved(),
+                    Requirement::hosts("link", 2),
+                ],
+            ),
+            Err(NetworkError::InsufficientAddressSpace {
+                index: 1,
+                hosts: 2,
+                required: 258,
+                available: 256,
+            })
+        );
+        assert_eq!(
+            Vlsm::with_requirements(
+                network("10.0.0.0/8"),
+                vec![Requirement::hosts("users", u32::MAX / 2)
+                    .with_growth(Growth::Factor(NonZeroU32::new(4).unwrap()))],
+            ),
+            Err(NetworkError::HostCountOutOfRange {
+                hosts: (u32::MAX / 2) as u64 * 4
+            })
+        );
     }
 }
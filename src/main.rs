@@ -0,0 +1,277 @@
+//! ipcalc-style command-line subnet calculator built on the subnet library.
+
+use std::env;
+use std::fmt;
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use subnet::{Cidr, Network, NetworkError, Slsm, Vlsm};
+
+const USAGE: &str = "\
+Usage:
+  subnet <network>                    Show information about a network
+  subnet slsm <network> <cidr>        Split a network into subnets of the given CIDR
+  subnet vlsm <network> <hosts>...    Allocate subnets for each required host count
+
+A network is given as \"ip_address/cidr\" or \"ip_address netmask\",
+for example 10.1.2.3/20 or \"10.1.2.3 255.255.240.0\".";
+
+/// Errors reported by the command-line interface.
+#[derive(Debug)]
+enum CliError {
+    Usage,
+    InvalidNumber(String),
+    Network(NetworkError),
+    Io(io::Error),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Usage => write!(f, "{USAGE}"),
+            Self::InvalidNumber(s) => write!(f, "error: \"{s}\" is not a valid number"),
+            Self::Network(err) => write!(f, "error: {err}"),
+            Self::Io(err) => write!(f, "error: {err}"),
+        }
+    }
+}
+
+impl From<NetworkError> for CliError {
+    fn from(err: NetworkError) -> Self {
+        Self::Network(err)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let stdout = io::stdout();
+    match run(&args, &mut stdout.lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        // Stop quietly when the output is closed early, e.g. when piped into head.
+        Err(CliError::Io(err)) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run the command given by args, writing the result to out.
+fn run(args: &[String], out: &mut impl Write) -> Result<(), CliError> {
+    match args.first().map(String::as_str) {
+        None => Err(CliError::Usage),
+        Some("-h") | Some("--help") | Some("help") => {
+            writeln!(out, "{USAGE}")?;
+            Ok(())
+        }
+        Some("slsm") => {
+            let (network, rest) = parse_network(&args[1..])?;
+            let [cidr] = rest else {
+                return Err(CliError::Usage);
+            };
+            let cidr = Cidr::new(parse_number(cidr)?)?;
+            write_subnets(out, Slsm::new(network, cidr)?)
+        }
+        Some("vlsm") => {
+            let (network, rest) = parse_network(&args[1..])?;
+            if rest.is_empty() {
+                return Err(CliError::Usage);
+            }
+            let required_hosts = rest
+                .iter()
+                .map(|hosts| parse_number(hosts))
+                .collect::<Result<Vec<u32>, _>>()?;
+            write_subnets(out, Vlsm::new(network, required_hosts)?)
+        }
+        Some(_) => {
+            let (network, rest) = parse_network(args)?;
+            if !rest.is_empty() {
+                return Err(CliError::Usage);
+            }
+            write_network_info(out, &network)
+        }
+    }
+}
+
+/// Parse a network from the start of args, either from a single "ip_address/cidr" or
+/// "ip_address netmask" argument, or from an ip_address and netmask given as two arguments.
+/// Return the network and the remaining arguments.
+fn parse_network(args: &[String]) -> Result<(Network, &[String]), CliError> {
+    let first = args.first().ok_or(CliError::Usage)?;
+    match Network::try_from(first.as_str()) {
+        Ok(network) => Ok((network, &args[1..])),
+        Err(err) => match args.get(1) {
+            Some(netmask) if !first.contains('/') => {
+                let network = Network::try_from(format!("{first} {netmask}").as_str())?;
+                Ok((network, &args[2..]))
+            }
+            _ => Err(err.into()),
+        },
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(s: &str) -> Result<T, CliError> {
+    s.parse()
+        .map_err(|_| CliError::InvalidNumber(s.to_string()))
+}
+
+fn write_network_info(out: &mut impl Write, network: &Network) -> Result<(), CliError> {
+    writeln!(out, "Network:    {network}")?;
+    writeln!(out, "Netmask:    {}", network.netmask())?;
+    writeln!(
+        out,
+        "Broadcast:  {}",
+        display_option(network.broadcast_address())
+    )?;
+    writeln!(
+        out,
+        "First host: {}",
+        display_option(network.first_host_address())
+    )?;
+    writeln!(
+        out,
+        "Last host:  {}",
+        display_option(network.last_host_address())
+    )?;
+    writeln!(out, "Hosts:      {}", network.number_of_hosts())?;
+    Ok(())
+}
+
+fn write_subnets(
+    out: &mut impl Write,
+    subnets: impl Iterator<Item = Network>,
+) -> Result<(), CliError> {
+    writeln!(
+        out,
+        "{:<18} {:<15} {:<15} {:<15} Hosts",
+        "Network", "First host", "Last host", "Broadcast"
+    )?;
+    for subnet in subnets {
+        writeln!(
+            out,
+            "{:<18} {:<15} {:<15} {:<15} {}",
+            subnet.to_string(),
+            display_option(subnet.first_host_address()),
+            display_option(subnet.last_host_address()),
+            display_option(subnet.broadcast_address()),
+            subnet.number_of_hosts()
+        )?;
+    }
+    Ok(())
+}
+
+fn display_option<T: fmt::Display>(value: Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_args(args: &[&str]) -> Result<String, CliError> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        let mut out = Vec::new();
+        run(&args, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn network_info() {
+        let want = "\
+Network:    10.1.0.0/20
+Netmask:    255.255.240.0
+Broadcast:  10.1.15.255
+First host: 10.1.0.1
+Last host:  10.1.15.254
+Hosts:      4094
+";
+        assert_eq!(run_args(&["10.1.2.3/20"]).unwrap(), want);
+        assert_eq!(run_args(&["10.1.2.3 255.255.240.0"]).unwrap(), want);
+        assert_eq!(run_args(&["10.1.2.3", "255.255.240.0"]).unwrap(), want);
+
+        let got = run_args(&["10.1.2.3/32"]).unwrap();
+        assert!(got.contains("Broadcast:  -\n"));
+        assert!(got.contains("Hosts:      0\n"));
+
+        assert!(matches!(
+            run_args(&["10.1.2.3/33"]),
+            Err(CliError::Network(NetworkError::CidrOutOfRangeError))
+        ));
+        assert!(matches!(
+            run_args(&["10.1.2/24"]),
+            Err(CliError::Network(NetworkError::IPv4AddressError))
+        ));
+        assert!(matches!(
+            run_args(&["10.1.2.3/24", "extra"]),
+            Err(CliError::Usage)
+        ));
+        assert!(matches!(run_args(&[]), Err(CliError::Usage)));
+        assert!(run_args(&["--help"]).unwrap().starts_with("Usage:"));
+    }
+
+    #[test]
+    fn slsm_subnets() {
+        let want = "\
+Network            First host      Last host       Broadcast       Hosts
+10.0.0.0/26        10.0.0.1        10.0.0.62       10.0.0.63       62
+10.0.0.64/26       10.0.0.65       10.0.0.126      10.0.0.127      62
+10.0.0.128/26      10.0.0.129      10.0.0.190      10.0.0.191      62
+10.0.0.192/26      10.0.0.193      10.0.0.254      10.0.0.255      62
+";
+        assert_eq!(run_args(&["slsm", "10.0.0.0/24", "26"]).unwrap(), want);
+        assert_eq!(
+            run_args(&["slsm", "10.0.0.0", "255.255.255.0", "26"]).unwrap(),
+            want
+        );
+
+        assert!(matches!(
+            run_args(&["slsm", "10.0.0.0/24", "23"]),
+            Err(CliError::Network(NetworkError::InvalidSubnetCidr))
+        ));
+        assert!(matches!(
+            run_args(&["slsm", "10.0.0.0/24", "x"]),
+            Err(CliError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            run_args(&["slsm", "10.0.0.0/24"]),
+            Err(CliError::Usage)
+        ));
+    }
+
+    #[test]
+    fn vlsm_subnets() {
+        let want = "\
+Network            First host      Last host       Broadcast       Hosts
+10.0.0.0/25        10.0.0.1        10.0.0.126      10.0.0.127      126
+10.0.0.128/26      10.0.0.129      10.0.0.190      10.0.0.191      62
+";
+        assert_eq!(
+            run_args(&["vlsm", "10.0.0.0/24", "60", "100"]).unwrap(),
+            want
+        );
+
+        assert!(matches!(
+            run_args(&["vlsm", "10.0.0.0/24", "200", "100"]),
+            Err(CliError::Network(
+                NetworkError::InsufficientAddressSpace { .. }
+            ))
+        ));
+        assert!(matches!(
+            run_args(&["vlsm", "10.0.0.0/24", "-1"]),
+            Err(CliError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            run_args(&["vlsm", "10.0.0.0/24"]),
+            Err(CliError::Usage)
+        ));
+    }
+}
@@ -1,13 +1,19 @@
 #![warn(missing_docs)]
 
 /// Utility for working calculating network subnets.
+use std::fmt;
 use std::net::Ipv4Addr;
 
+use crate::aggregate::{merge_ranges, range_to_networks};
+use crate::special_purpose::classify_network;
+use crate::Addresses;
 use crate::Cidr;
 use crate::NetworkError;
+use crate::{AddressClass, Classification};
 
 /// Represents a IPv4 Network by storing it's Network address and CIDR value.
-#[derive(Debug, PartialEq, Eq, Clone)]
+/// Networks are ordered by Network address, then by CIDR value.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 pub struct Network {
     network_id: Ipv4Addr,
     cidr: Cidr,
@@ -66,7 +72,7 @@ impl Network {
     }
 
     /// Return the number of hosts available in the subnet
-    pub fn number_of_hosts(&self) -> u32 {
+    pub fn number_of_hosts(&self) -> u64 {
         if *self.cidr == 32 {
             return 0;
         }
@@ -74,7 +80,143 @@ impl Network {
         if *self.cidr == 31 {
             return 2;
         }
-        2_u32.pow(32 - *self.cidr as u32) - 2
+        self.number_of_addresses() - 2
+    }
+
+    /// Return the number of addresses in the subnet, including the network and broadcast addresses
+    pub fn number_of_addresses(&self) -> u64 {
+        1_u64 << (32 - *self.cidr as u32)
+    }
+
+    /// Return an iterator over the host addresses in the subnet, from the first host address to the last.
+    /// Like number_of_hosts, both addresses of a /31 are hosts and a /32 has none.
+    pub fn hosts(&self) -> Addresses {
+        match (self.first_host_address(), self.last_host_address()) {
+            (Some(first), Some(last)) => Addresses::new(u32::from(first), u32::from(last)),
+            _ => Addresses::empty(),
+        }
+    }
+
+    /// Return an iterator over every address in the subnet, including the network and broadcast addresses
+    pub fn addresses(&self) -> Addresses {
+        Addresses::new(u32::from(self.network_id), self.last_address_u32())
+    }
+}
+
+/// Describes how one Network relates to another.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Relation {
+    /// Both Networks cover the same addresses
+    Equal,
+    /// The Network is contained within the other Network
+    Subnet,
+    /// The Network contains the other Network
+    Supernet,
+    /// The Networks share no addresses and are not next to each other
+    Disjoint,
+    /// The Networks share no addresses, but one starts directly after the other ends
+    Adjacent,
+}
+
+impl Network {
+    /// Return true if the address is inside the Network, including the network and broadcast addresses.
+    pub fn contains(&self, ip_address: Ipv4Addr) -> bool {
+        u32::from(ip_address) & self.cidr.to_bitmask() == u32::from(self.network_id)
+    }
+
+    /// Return true if every address of the other Network is inside this Network.
+    pub fn contains_network(&self, other: &Network) -> bool {
+        self.cidr <= other.cidr && self.contains(other.network_id)
+    }
+
+    /// Return true if the Networks share at least one address.
+    pub fn overlaps(&self, other: &Network) -> bool {
+        self.contains_network(other) || other.contains_network(self)
+    }
+
+    /// Return how this Network relates to the other Network.
+    pub fn relation(&self, other: &Network) -> Relation {
+        if self == other {
+            Relation::Equal
+        } else if other.contains_network(self) {
+            Relation::Subnet
+        } else if self.contains_network(other) {
+            Relation::Supernet
+        } else if self.last_address_u32().checked_add(1) == Some(u32::from(other.network_id))
+            || other.last_address_u32().checked_add(1) == Some(u32::from(self.network_id))
+        {
+            Relation::Adjacent
+        } else {
+            Relation::Disjoint
+        }
+    }
+
+    /// Return the minimal sorted list of Networks covering the addresses of this Network that are not in any of the excluded Networks.
+    /// Return a NetworkOutsideBase error if an excluded Network is not inside this Network.
+    pub fn exclude(&self, excluded: &[Network]) -> Result<Vec<Network>, NetworkError> {
+        if let Some(network) = excluded
+            .iter()
+            .find(|network| !self.contains_network(network))
+        {
+            return Err(NetworkError::NetworkOutsideBase {
+                network: network.clone(),
+            });
+        }
+
+        let excluded = merge_ranges(
+            excluded
+                .iter()
+                .map(|network| (u32::from(network.network_id), network.last_address_u32()))
+                .collect(),
+        );
+
+        let mut remaining = Vec::new();
+        let mut next = u32::from(self.network_id) as u64;
+        for (first, last) in excluded {
+            if next < first as u64 {
+                remaining.extend(range_to_networks(next as u32, first - 1));
+            }
+            next = last as u64 + 1;
+        }
+        if next <= self.last_address_u32() as u64 {
+            remaining.extend(range_to_networks(next as u32, self.last_address_u32()));
+        }
+        Ok(remaining)
+    }
+
+    /// Return the buddy of the Network, the other half of the Network one prefix bit shorter
+    /// that contains it, or None for a /0.
+    pub fn buddy(&self) -> Option<Network> {
+        if *self.cidr == 0 {
+            return None;
+        }
+        let size = self.number_of_addresses() as u32;
+        Some(Self {
+            network_id: Ipv4Addr::from(u32::from(self.network_id) ^ size),
+            cidr: self.cidr,
+        })
+    }
+
+    /// Return the classification of the subnet's addresses according to the IANA special-purpose address registries,
+    /// as the minimal sorted list of Networks in which every address has the same classification.
+    /// A subnet inside a single registry block is returned as the only Network in the list.
+    pub fn classify(&self) -> Vec<(Network, Classification)> {
+        classify_network(self)
+    }
+
+    /// Return the class shared by every address in the subnet, or None if the subnet straddles more than one class.
+    pub fn address_class(&self) -> Option<AddressClass> {
+        let parts = self.classify();
+        let class = parts.first()?.1.class();
+        parts
+            .iter()
+            .all(|(_, classification)| classification.class() == class)
+            .then_some(class)
+    }
+
+    /// Return the last address in the Network as a u32. Unlike broadcast_address, this is defined for a /32.
+    pub(crate) fn last_address_u32(&self) -> u32 {
+        u32::from(self.network_id) | !self.cidr.to_bitmask()
     }
 }
 
@@ -106,6 +248,13 @@ impl TryFrom<&str> for Network {
     }
 }
 
+impl fmt::Display for Network {
+    /// Format the Network as "ip_address/cidr".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network_id, *self.cidr)
+    }
+}
+
 impl Network {
     /// Return the Network Id.
     pub fn network_id(&self) -> Ipv4Addr {
@@ -116,12 +265,17 @@ impl Network {
     pub fn cidr(&self) -> Cidr {
         self.cidr
     }
+
+    /// Return the netmask, the CIDR value in dotted decimal notation.
+    pub fn netmask(&self) -> Ipv4Addr {
+        Ipv4Addr::from(self.cidr.to_bitmask())
+    }
 }
 
 impl Network {
     /// Parse str and convert to IPv4 address
     /// Expects str to contain 4 decimal octets seperated by dots
-    fn try_str_to_ipv4addr(s: &str) -> Result<Ipv4Addr, NetworkError> {
+    pub(crate) fn try_str_to_ipv4addr(s: &str) -> Result<Ipv4Addr, NetworkError> {
         let mut octets = Vec::with_capacity(4);
         for octet in s.split('.') {
             match octet.parse::<u8>() {
@@ -1,13 +1,125 @@
 #![warn(missing_docs)]
+
+use std::fmt;
+
+use crate::Network;
+
 /// NetworkError enumerates the errors returned by the subnet library.
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "error", content = "details")
+)]
 pub enum NetworkError {
-    /// The value given as a CIDR is greater than 32
+    /// The value given as a CIDR is greater than 32 for IPv4, or greater than 128 for IPv6
     CidrOutOfRangeError,
     /// There was an error parsing the str into an IPv4 address
     IPv4AddressError,
+    /// There was an error parsing the str into an IPv6 address
+    IPv6AddressError,
     /// Malformed string when parsing str
     ParsingError,
     /// The subnet cidr is less than the network cidr
     InvalidSubnetCidr,
+    /// The required hosts do not fit in the base network.
+    InsufficientAddressSpace {
+        /// Position in the list of required hosts of the first requirement that does not fit
+        index: usize,
+        /// Number of hosts of the first requirement that does not fit
+        hosts: u32,
+        /// Number of addresses required to allocate every requirement
+        required: u128,
+        /// Number of addresses available in the base network
+        available: u128,
+    },
+    /// No Network has room for the number of hosts
+    HostCountOutOfRange {
+        /// The number of hosts, including any growth
+        hosts: u64,
+    },
+    /// A Network cannot be split into the number of subnets
+    SubnetCountOutOfRange {
+        /// The CIDR of the Network being split
+        cidr: u8,
+        /// The number of subnets
+        subnets: u64,
+    },
+    /// There is no free block large enough for the CIDR left in the pool
+    PoolExhausted {
+        /// The CIDR of the requested network
+        cidr: u8,
+    },
+    /// The network overlaps a network that is already allocated from the pool
+    AllocationConflict {
+        /// The network that was requested
+        network: Network,
+        /// The allocated network it overlaps
+        allocated: Network,
+    },
+    /// The network is not allocated from the pool
+    NotAllocated {
+        /// The network that is not allocated
+        network: Network,
+    },
+    /// The first address of a range is greater than the last address
+    InvalidRange,
+    /// The network is not contained in the base network
+    NetworkOutsideBase {
+        /// The network that is not contained in the base network
+        network: Network,
+    },
+    /// There are enough free addresses for a requirement, but no contiguous free block is large enough.
+    FragmentedAddressSpace {
+        /// Position in the list of requirements of the first requirement that does not fit
+        index: usize,
+        /// Number of hosts of the first requirement that does not fit
+        hosts: u32,
+        /// CIDR of the free block the requirement needs
+        cidr: u8,
+    },
 }
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CidrOutOfRangeError => write!(f, "CIDR is out of range for the address family"),
+            Self::IPv4AddressError => write!(f, "invalid IPv4 address"),
+            Self::IPv6AddressError => write!(f, "invalid IPv6 address"),
+            Self::ParsingError => write!(f, "malformed network string"),
+            Self::InvalidSubnetCidr => write!(f, "subnet CIDR is less than the network CIDR"),
+            Self::InsufficientAddressSpace {
+                index,
+                hosts,
+                required,
+                available,
+            } => write!(
+                f,
+                "requirement {index} for {hosts} hosts does not fit: {required} addresses required, {available} available"
+            ),
+            Self::HostCountOutOfRange { hosts } => {
+                write!(f, "no IPv4 network has room for {hosts} hosts")
+            }
+            Self::SubnetCountOutOfRange { cidr, subnets } => {
+                write!(f, "a /{cidr} network cannot be split into {subnets} subnets")
+            }
+            Self::PoolExhausted { cidr } => write!(f, "no free /{cidr} block left in the pool"),
+            Self::AllocationConflict { network, allocated } => {
+                write!(f, "network {network} overlaps allocated network {allocated}")
+            }
+            Self::NotAllocated { network } => write!(f, "network {network} is not allocated"),
+            Self::InvalidRange => {
+                write!(f, "first address of the range is greater than the last address")
+            }
+            Self::NetworkOutsideBase { network } => {
+                write!(f, "network {network} is not inside the base network")
+            }
+            Self::FragmentedAddressSpace { index, hosts, cidr } => write!(
+                f,
+                "requirement {index} for {hosts} hosts does not fit: no contiguous free block of /{cidr}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
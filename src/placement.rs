@@ -0,0 +1,53 @@
+#![warn(missing_docs)]
+
+use std::cmp::Reverse;
+
+use crate::{Cidr, IpSet, Network};
+
+/// Chooses where in the free space of a base Network a subnet is allocated, so that Vlsm and Ledger
+/// allocations can be laid out with different strategies.
+pub trait Placement {
+    /// Return the subnet with the given CIDR to allocate from the free space, or None if no free block is large enough.
+    fn place(&self, free: &IpSet, cidr: Cidr) -> Option<Network>;
+}
+
+/// Places each subnet at the start of the lowest free block it fits in.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct FirstFit;
+
+/// Places each subnet at the start of the smallest free block it fits in, so that larger blocks stay available.
+/// Ties are broken by the lowest address.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct BestFit;
+
+/// Places each subnet at the end of the highest free block it fits in, filling the base network from the top
+/// so that low addresses stay free.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct TopDown;
+
+impl Placement for FirstFit {
+    fn place(&self, free: &IpSet, cidr: Cidr) -> Option<Network> {
+        let block = free.networks().find(|block| block.cidr() <= cidr)?;
+        Network::new(block.network_id(), cidr).ok()
+    }
+}
+
+impl Placement for BestFit {
+    fn place(&self, free: &IpSet, cidr: Cidr) -> Option<Network> {
+        let block = free
+            .networks()
+            .filter(|block| block.cidr() <= cidr)
+            .max_by_key(|block| (block.cidr(), Reverse(block.network_id())))?;
+        Network::new(block.network_id(), cidr).ok()
+    }
+}
+
+impl Placement for TopDown {
+    fn place(&self, free: &IpSet, cidr: Cidr) -> Option<Network> {
+        let block = free
+            .networks()
+            .filter(|block| block.cidr() <= cidr)
+            .last()?;
+        Network::new(block.last_address_u32().into(), cidr).ok()
+    }
+}
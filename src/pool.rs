@@ -0,0 +1,158 @@
+#![warn(missing_docs)]
+
+use std::collections::BTreeSet;
+
+use crate::{BestFit, Cidr, IpSet, Network, NetworkError, Placement, Subnetting};
+
+/// Allocates subnets of a base Network and keeps track of which subnets are taken, so they can be handed out
+/// and released over time.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Pool {
+    base_network: Network,
+    allocated: BTreeSet<Network>,
+    free: IpSet,
+}
+
+impl Pool {
+    /// Create and initialise a new Pool with every address of the base network free.
+    pub fn new(base_network: Network) -> Self {
+        let free = IpSet::from(base_network.clone());
+        Self {
+            base_network,
+            allocated: BTreeSet::new(),
+            free,
+        }
+    }
+
+    /// Return the base network from the Pool.
+    pub fn base_network(&self) -> &Network {
+        &self.base_network
+    }
+
+    /// Allocate and return a free subnet with the given CIDR.
+    /// The subnet is taken from the smallest free block it fits in, so that larger blocks stay available.
+    /// Return an InvalidSubnetCidr error if the CIDR is less than the base network CIDR,
+    /// or a PoolExhausted error if no free block is large enough.
+    pub fn allocate(&mut self, cidr: Cidr) -> Result<Network, NetworkError> {
+        if cidr < self.base_network.cidr() {
+            return Err(NetworkError::InvalidSubnetCidr);
+        }
+
+        let network = BestFit
+            .place(&self.free, cidr)
+            .ok_or(NetworkError::PoolExhausted { cidr: *cidr })?;
+        self.insert(network.clone());
+        Ok(network)
+    }
+
+    /// Allocate and return the smallest free subnet with room for the required number of hosts.
+    /// Return a HostCountOutOfRange error if no network has room for the hosts,
+    /// otherwise the errors of allocate.
+    pub fn allocate_hosts(&mut self, hosts: u32) -> Result<Network, NetworkError> {
+        self.allocate(Cidr::for_host_count(hosts)?)
+    }
+
+    /// Allocate exactly the given network.
+    /// Return a NetworkOutsideBase error if the network is not inside the base network,
+    /// or an AllocationConflict error if it overlaps a network that is already allocated.
+    pub fn allocate_specific(&mut self, network: Network) -> Result<(), NetworkError> {
+        if !self.base_network.contains_network(&network) {
+            return Err(NetworkError::NetworkOutsideBase { network });
+        }
+        if let Some(allocated) = self.find_overlap(&network) {
+            return Err(NetworkError::AllocationConflict {
+                allocated: allocated.clone(),
+                network,
+            });
+        }
+
+        self.insert(network);
+        Ok(())
+    }
+
+    /// Release a network previously allocated from the Pool, making its addresses free again.
+    /// Return a NotAllocated error if the network is not exactly one of the allocated networks.
+    pub fn release(&mut self, network: &Network) -> Result<(), NetworkError> {
+        if !self.allocated.remove(network) {
+            return Err(NetworkError::NotAllocated {
+                network: network.clone(),
+            });
+        }
+
+        self.free = self.free.union(&IpSet::from(network.clone()));
+        Ok(())
+    }
+
+    /// Return true if the network is exactly one of the allocated networks.
+    pub fn is_allocated(&self, network: &Network) -> bool {
+        self.allocated.contains(network)
+    }
+
+    /// Return an iterator over the allocated networks, ordered by Network.
+    pub fn allocated(&self) -> impl Iterator<Item = &Network> + '_ {
+        self.allocated.iter()
+    }
+
+    /// Return the free addresses of the Pool.
+    pub fn free(&self) -> &IpSet {
+        &self.free
+    }
+
+    /// Return the number of free addresses in the Pool.
+    pub fn free_addresses(&self) -> u64 {
+        self.free.len()
+    }
+
+    /// Return an iterator over the free space as the minimal sorted list of Networks.
+    pub fn free_networks(&self) -> impl Iterator<Item = Network> + '_ {
+        self.free.networks()
+    }
+
+    /// Return the largest free block, or None if the Pool is full. Ties are broken by the lowest address.
+    pub fn largest_free_block(&self) -> Option<Network> {
+        self.free
+            .networks()
+            .min_by_key(|block| (block.cidr(), block.network_id()))
+    }
+
+    fn insert(&mut self, network: Network) {
+        self.free = self.free.difference(&IpSet::from(network.clone()));
+        self.allocated.insert(network);
+    }
+
+    /// Return an allocated network overlapping the network, if there is one.
+    fn find_overlap(&self, network: &Network) -> Option<&Network> {
+        // Allocated networks are disjoint, so only the last one ordered before the network can contain it,
+        // and if any are inside the network then the first one ordered after it is.
+        let before = self.allocated.range(..=network).next_back();
+        let after = self.allocated.range(network..).next();
+        before
+            .into_iter()
+            .chain(after)
+            .find(|allocated| allocated.overlaps(network))
+    }
+}
+
+impl Subnetting for Pool {
+    type Subnets = std::vec::IntoIter<Network>;
+
+    fn base_network(&self) -> &Network {
+        &self.base_network
+    }
+
+    fn subnets(&self) -> Self::Subnets {
+        self.allocated
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn subnet_count(&self) -> u64 {
+        self.allocated.len() as u64
+    }
+
+    fn used_addresses(&self) -> u64 {
+        self.base_network.number_of_addresses() - self.free.len()
+    }
+}
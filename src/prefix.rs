@@ -0,0 +1,58 @@
+#![warn(missing_docs)]
+
+use std::ops::Deref;
+
+use crate::{Cidr, Ipv6Cidr, NetworkError};
+
+/// Holds a prefix length for either address family.
+/// The range is checked against the address family when the Prefix is applied to an address.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+pub struct Prefix {
+    inner: u8,
+}
+
+impl Prefix {
+    /// Create and initialise a new Prefix struct. Accepts values up to 128, the IPv6 maximum.
+    pub fn new(prefix: u8) -> Result<Self, NetworkError> {
+        if prefix > 128 {
+            return Err(NetworkError::CidrOutOfRangeError);
+        }
+        Ok(Self { inner: prefix })
+    }
+}
+
+impl Deref for Prefix {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl From<Cidr> for Prefix {
+    fn from(cidr: Cidr) -> Self {
+        Self { inner: *cidr }
+    }
+}
+
+impl From<Ipv6Cidr> for Prefix {
+    fn from(cidr: Ipv6Cidr) -> Self {
+        Self { inner: *cidr }
+    }
+}
+
+impl TryFrom<Prefix> for Cidr {
+    type Error = NetworkError;
+
+    fn try_from(prefix: Prefix) -> Result<Cidr, NetworkError> {
+        Cidr::new(*prefix)
+    }
+}
+
+impl TryFrom<Prefix> for Ipv6Cidr {
+    type Error = NetworkError;
+
+    fn try_from(prefix: Prefix) -> Result<Ipv6Cidr, NetworkError> {
+        Ipv6Cidr::new(*prefix)
+    }
+}
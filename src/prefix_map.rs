@@ -0,0 +1,365 @@
+#![warn(missing_docs)]
+
+use std::net::Ipv4Addr;
+
+use crate::{Cidr, Network};
+
+/// A map keyed by Network that supports longest prefix matching of addresses.
+/// Entries are stored in a path compressed binary trie, so lookups take at most 32 steps regardless of the number of entries.
+#[derive(Debug, Clone)]
+pub struct PrefixMap<V> {
+    // Nodes are stored in an arena and refer to each other by index. Index 0 is the 0.0.0.0/0 root, which is always present.
+    nodes: Vec<Node<V>>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    prefix: u32,
+    cidr: u8,
+    value: Option<V>,
+    children: [Option<u32>; 2],
+}
+
+impl<V> Node<V> {
+    fn new(prefix: u32, cidr: u8, value: Option<V>) -> Self {
+        Self {
+            prefix,
+            cidr,
+            value,
+            children: [None, None],
+        }
+    }
+
+    /// Return true if the node's prefix covers the first cidr bits of key, and cidr is at least the node's cidr.
+    fn covers(&self, key: u32, cidr: u8) -> bool {
+        cidr >= self.cidr && common_prefix_len(self.prefix, key) >= self.cidr
+    }
+
+    fn network(&self) -> Network {
+        Network::new(
+            Ipv4Addr::from(self.prefix),
+            Cidr::new(self.cidr).expect("node cidr is at most 32"),
+        )
+        .expect("Network::new never fails")
+    }
+}
+
+/// Return the number of leading bits that a and b have in common.
+fn common_prefix_len(a: u32, b: u32) -> u8 {
+    (a ^ b).leading_zeros() as u8
+}
+
+/// Return the bit of key following the first cidr bits, which selects the child to descend into.
+fn bit_after(key: u32, cidr: u8) -> usize {
+    ((key >> (31 - cidr)) & 1) as usize
+}
+
+fn mask(key: u32, cidr: u8) -> u32 {
+    key & Cidr::new(cidr).expect("cidr is at most 32").to_bitmask()
+}
+
+fn key(network: &Network) -> (u32, u8) {
+    (u32::from(network.network_id()), *network.cidr())
+}
+
+impl<V> Default for PrefixMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> PrefixMap<V> {
+    /// Create and initialise a new empty PrefixMap.
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::new(0, 0, None)],
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Return the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert a value for the Network, returning the previous value for the Network if there was one.
+    pub fn insert(&mut self, network: Network, value: V) -> Option<V> {
+        let (key, cidr) = key(&network);
+        let mut index = 0;
+        loop {
+            let node = &mut self.nodes[index as usize];
+            if node.cidr == cidr {
+                let previous = node.value.replace(value);
+                if previous.is_none() {
+                    self.len += 1;
+                }
+                return previous;
+            }
+
+            let bit = bit_after(key, node.cidr);
+            let Some(child_index) = node.children[bit] else {
+                let leaf = self.alloc(Node::new(key, cidr, Some(value)));
+                self.nodes[index as usize].children[bit] = Some(leaf);
+                self.len += 1;
+                return None;
+            };
+
+            let child = &self.nodes[child_index as usize];
+            if child.covers(key, cidr) {
+                index = child_index;
+                continue;
+            }
+
+            // The child does not cover the key, so the new entry, or a new node for the bits they have in common,
+            // is placed between this node and the child.
+            let common = common_prefix_len(child.prefix, key).min(cidr);
+            let child_bit = bit_after(child.prefix, common);
+            let new_index = if common == cidr {
+                let mut new_node = Node::new(key, cidr, Some(value));
+                new_node.children[child_bit] = Some(child_index);
+                self.alloc(new_node)
+            } else {
+                let leaf = self.alloc(Node::new(key, cidr, Some(value)));
+                let mut glue = Node::new(mask(key, common), common, None);
+                glue.children[child_bit] = Some(child_index);
+                glue.children[1 - child_bit] = Some(leaf);
+                self.alloc(glue)
+            };
+            self.nodes[index as usize].children[bit] = Some(new_index);
+            self.len += 1;
+            return None;
+        }
+    }
+
+    /// Remove the entry for the Network, returning its value if there was one.
+    pub fn remove(&mut self, network: &Network) -> Option<V> {
+        let (key, cidr) = key(network);
+        let mut path = Vec::new();
+        let mut index = 0;
+        while self.nodes[index as usize].cidr < cidr {
+            let bit = bit_after(key, self.nodes[index as usize].cidr);
+            let child_index = self.nodes[index as usize].children[bit]?;
+            if !self.nodes[child_index as usize].covers(key, cidr) {
+                return None;
+            }
+            path.push((index, bit));
+            index = child_index;
+        }
+        if self.nodes[index as usize].cidr != cidr {
+            return None;
+        }
+
+        let value = self.nodes[index as usize].value.take()?;
+        self.len -= 1;
+
+        // Remove nodes left without a value and with fewer than two children, as they no longer mark a branch.
+        while let Some((parent, bit)) = path.pop() {
+            let node = &self.nodes[index as usize];
+            if node.value.is_some() {
+                break;
+            }
+            match node.children {
+                [Some(_), Some(_)] => break,
+                [Some(child), None] | [None, Some(child)] => {
+                    self.nodes[parent as usize].children[bit] = Some(child);
+                    self.free.push(index);
+                    break;
+                }
+                [None, None] => {
+                    self.nodes[parent as usize].children[bit] = None;
+                    self.free.push(index);
+                    index = parent;
+                }
+            }
+        }
+        Some(value)
+    }
+
+    /// Return the value for exactly the Network.
+    pub fn get(&self, network: &Network) -> Option<&V> {
+        let index = self.find(network)?;
+        self.nodes[index as usize].value.as_ref()
+    }
+
+    /// Return a mutable reference to the value for exactly the Network.
+    pub fn get_mut(&mut self, network: &Network) -> Option<&mut V> {
+        let index = self.find(network)?;
+        self.nodes[index as usize].value.as_mut()
+    }
+
+    /// Return true if the map contains an entry for exactly the Network.
+    pub fn contains_key(&self, network: &Network) -> bool {
+        self.get(network).is_some()
+    }
+
+    fn find(&self, network: &Network) -> Option<u32> {
+        let (key, cidr) = key(network);
+        let mut index = 0;
+        loop {
+            let node = &self.nodes[index as usize];
+            if node.cidr == cidr {
+                return Some(index);
+            }
+            let child_index = node.children[bit_after(key, node.cidr)]?;
+            if !self.nodes[child_index as usize].covers(key, cidr) {
+                return None;
+            }
+            index = child_index;
+        }
+    }
+
+    /// Return the entry with the longest prefix containing the address.
+    pub fn longest_match(&self, ip_address: Ipv4Addr) -> Option<(Network, &V)> {
+        let key = u32::from(ip_address);
+        let mut longest = None;
+        let mut index = Some(0);
+        while let Some(current) = index {
+            let node = &self.nodes[current as usize];
+            if !node.covers(key, 32) {
+                break;
+            }
+            if node.value.is_some() {
+                longest = Some(node);
+            }
+            index = if node.cidr < 32 {
+                node.children[bit_after(key, node.cidr)]
+            } else {
+                None
+            };
+        }
+        longest.map(|node| {
+            (
+                node.network(),
+                node.value.as_ref().expect("node has a value"),
+            )
+        })
+    }
+
+    /// Return the entries whose Network contains the given Network, including an entry for the Network itself,
+    /// from the shortest prefix to the longest.
+    pub fn covering(&self, network: &Network) -> impl Iterator<Item = (Network, &V)> + '_ {
+        let (key, cidr) = key(network);
+        let mut path = Vec::new();
+        let mut index = Some(0);
+        while let Some(current) = index {
+            let node = &self.nodes[current as usize];
+            if !node.covers(key, cidr) {
+                break;
+            }
+            if let Some(value) = node.value.as_ref() {
+                path.push((node.network(), value));
+            }
+            index = if node.cidr < cidr {
+                node.children[bit_after(key, node.cidr)]
+            } else {
+                None
+            };
+        }
+        path.into_iter()
+    }
+
+    /// Return the entries whose Network is contained in the given Network, including an entry for the Network itself,
+    /// ordered by Network.
+    pub fn covered(&self, network: &Network) -> Iter<'_, V> {
+        let (key, cidr) = key(network);
+        let mut index = 0;
+        loop {
+            let node = &self.nodes[index as usize];
+            if node.cidr >= cidr {
+                // Every node below here shares the node's prefix, so the subtree is covered if the node is.
+                let stack = if common_prefix_len(node.prefix, key) >= cidr {
+                    vec![index]
+                } else {
+                    Vec::new()
+                };
+                return Iter { map: self, stack };
+            }
+            match node.children[bit_after(key, node.cidr)] {
+                Some(child_index) => index = child_index,
+                None => {
+                    return Iter {
+                        map: self,
+                        stack: Vec::new(),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return an iterator over every entry in the map, ordered by Network.
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter {
+            map: self,
+            stack: vec![0],
+        }
+    }
+
+    fn alloc(&mut self, node: Node<V>) -> u32 {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index as usize] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                (self.nodes.len() - 1) as u32
+            }
+        }
+    }
+}
+
+/// Iterator over the entries of a PrefixMap, ordered by Network.
+#[derive(Debug, Clone)]
+pub struct Iter<'a, V> {
+    map: &'a PrefixMap<V>,
+    stack: Vec<u32>,
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (Network, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Visiting a node before its children, and the zero child before the one child, gives the order of Network.
+        while let Some(index) = self.stack.pop() {
+            let node = &self.map.nodes[index as usize];
+            self.stack.extend(node.children.iter().rev().flatten());
+            if let Some(value) = node.value.as_ref() {
+                return Some((node.network(), value));
+            }
+        }
+        None
+    }
+}
+
+impl<'a, V> IntoIterator for &'a PrefixMap<V> {
+    type Item = (Network, &'a V);
+    type IntoIter = Iter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<V> FromIterator<(Network, V)> for PrefixMap<V> {
+    fn from_iter<I: IntoIterator<Item = (Network, V)>>(entries: I) -> Self {
+        let mut map = PrefixMap::new();
+        map.extend(entries);
+        map
+    }
+}
+
+impl<V> Extend<(Network, V)> for PrefixMap<V> {
+    fn extend<I: IntoIterator<Item = (Network, V)>>(&mut self, entries: I) {
+        for (network, value) in entries {
+            self.insert(network, value);
+        }
+    }
+}
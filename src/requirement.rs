@@ -0,0 +1,228 @@
+#![warn(missing_docs)]
+
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+
+use crate::{Cidr, Network, NetworkError};
+
+/// The size or position of the subnet needed by a Requirement.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum RequirementKind {
+    /// The smallest subnet with room for the number of hosts
+    Hosts(u32),
+    /// A subnet with the given CIDR
+    Cidr(Cidr),
+    /// Exactly the given network, which is reserved before any other requirement is placed
+    #[cfg_attr(feature = "serde", serde(rename = "network"))]
+    Pinned(Network),
+}
+
+/// Extra room added to a Requirement for a number of hosts, so the subnet can grow without being renumbered.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Growth {
+    /// Multiply the number of hosts by the factor, which cannot be zero so that growth never shrinks a subnet
+    Factor(NonZeroU32),
+    /// Add the percentage of the number of hosts, rounded up
+    Percent(u32),
+}
+
+impl Growth {
+    /// Return the number of hosts after growth.
+    fn apply(self, hosts: u32) -> u64 {
+        let hosts = hosts as u64;
+        match self {
+            Growth::Factor(factor) => hosts * factor.get() as u64,
+            Growth::Percent(percent) => hosts + (hosts * percent as u64).div_ceil(100),
+        }
+    }
+}
+
+/// A named subnet requirement for a Vlsm, such as the hosts needed by a VLAN or site.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Requirement {
+    label: String,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    kind: RequirementKind,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    growth: Option<Growth>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    reserve_buddy: bool,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    metadata: BTreeMap<String, String>,
+}
+
+impl Requirement {
+    /// Create and initialise a new Requirement for a subnet with room for the number of hosts.
+    pub fn hosts(label: impl Into<String>, hosts: u32) -> Self {
+        Self::new(label.into(), RequirementKind::Hosts(hosts))
+    }
+
+    /// Create and initialise a new Requirement for a subnet with the given CIDR, such as a /31 for a link.
+    pub fn cidr(label: impl Into<String>, cidr: Cidr) -> Self {
+        Self::new(label.into(), RequirementKind::Cidr(cidr))
+    }
+
+    /// Create and initialise a new Requirement for exactly the given network.
+    pub fn pinned(label: impl Into<String>, network: Network) -> Self {
+        Self::new(label.into(), RequirementKind::Pinned(network))
+    }
+
+    fn new(label: String, kind: RequirementKind) -> Self {
+        Self {
+            label,
+            kind,
+            growth: None,
+            reserve_buddy: false,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Return the Requirement with room for the number of hosts to grow. Growth only applies to requirements
+    /// for a number of hosts, as CIDR and pinned requirements already fix the size of their subnet.
+    pub fn with_growth(mut self, growth: Growth) -> Self {
+        self.growth = Some(growth);
+        self
+    }
+
+    /// Return the Requirement with the adjacent buddy block of its subnet reserved as well,
+    /// so the subnet can later be widened by one prefix bit in place.
+    pub fn with_buddy_reserved(mut self) -> Self {
+        self.reserve_buddy = true;
+        self
+    }
+
+    /// Return the Requirement with a metadata value added, replacing any previous value for the key.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Return the label of the Requirement.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Return the size or position of the subnet needed.
+    pub fn kind(&self) -> &RequirementKind {
+        &self.kind
+    }
+
+    /// Return the number of hosts required, or None if the Requirement is for a CIDR or a pinned network.
+    pub fn host_count(&self) -> Option<u32> {
+        match self.kind {
+            RequirementKind::Hosts(hosts) => Some(hosts),
+            _ => None,
+        }
+    }
+
+    /// Return the growth of the Requirement, if any.
+    pub fn growth(&self) -> Option<Growth> {
+        self.growth
+    }
+
+    /// Return true if the buddy block of the subnet is reserved as well.
+    pub fn reserves_buddy(&self) -> bool {
+        self.reserve_buddy
+    }
+
+    /// Return the metadata of the Requirement.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Return the number of hosts to plan for, including growth,
+    /// or None if the Requirement is for a CIDR or a pinned network.
+    pub fn planned_host_count(&self) -> Option<u64> {
+        let hosts = self.host_count()?;
+        Some(match self.growth {
+            Some(growth) => growth.apply(hosts),
+            None => hosts as u64,
+        })
+    }
+
+    /// Return the CIDR of the subnet needed, including growth.
+    /// Return a HostCountOutOfRange error with the planned number of hosts if no network has room for them.
+    pub(crate) fn required_cidr(&self) -> Result<Cidr, NetworkError> {
+        match &self.kind {
+            RequirementKind::Hosts(hosts) => {
+                let planned = self.planned_host_count().unwrap_or(*hosts as u64);
+                u32::try_from(planned)
+                    .ok()
+                    .and_then(|planned| Cidr::for_host_count(planned).ok())
+                    .ok_or(NetworkError::HostCountOutOfRange { hosts: planned })
+            }
+            RequirementKind::Cidr(cidr) => Ok(*cidr),
+            RequirementKind::Pinned(network) => Ok(network.cidr()),
+        }
+    }
+}
+
+/// A subnet allocated by a Vlsm for a Requirement.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Allocation {
+    index: usize,
+    requirement: Requirement,
+    network: Network,
+    buddy: Option<Network>,
+}
+
+impl Allocation {
+    pub(crate) fn new(
+        index: usize,
+        requirement: Requirement,
+        network: Network,
+        buddy: Option<Network>,
+    ) -> Self {
+        Self {
+            index,
+            requirement,
+            network,
+            buddy,
+        }
+    }
+
+    /// Return the position of the Requirement in the list of requirements given to the Vlsm.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Return the Requirement the subnet was allocated for.
+    pub fn requirement(&self) -> &Requirement {
+        &self.requirement
+    }
+
+    /// Return the label of the Requirement the subnet was allocated for.
+    pub fn label(&self) -> &str {
+        self.requirement.label()
+    }
+
+    /// Return the allocated subnet.
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    /// Return the buddy block reserved next to the subnet, if the Requirement asked for one.
+    pub fn reserved_buddy(&self) -> Option<&Network> {
+        self.buddy.as_ref()
+    }
+}
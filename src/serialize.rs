@@ -0,0 +1,170 @@
+#![warn(missing_docs)]
+
+//! serde support, enabled by the "serde" feature.
+//! Networks are serialized as "ip_address/cidr" strings and deserialized with their TryFrom<&str> implementations,
+//! so the "ip_address netmask" format is also accepted for IPv4.
+//! Slsm and Vlsm are serialized as the arguments used to create them and recreated when deserialized.
+//! A Vlsm also stores its allocated subnets, which are restored rather than planned again.
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    Cidr, IpNetwork, Ipv4Range, Ipv6Cidr, Ipv6Network, Network, Prefix, Requirement,
+    RequirementKind, Slsm, Vlsm,
+};
+
+macro_rules! serde_as_str {
+    ($type:ty) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                <$type>::try_from(s.as_str()).map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+serde_as_str!(Network);
+serde_as_str!(Ipv6Network);
+serde_as_str!(IpNetwork);
+serde_as_str!(Ipv4Range);
+
+macro_rules! serde_as_u8 {
+    ($type:ty) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_u8(**self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                <$type>::new(u8::deserialize(deserializer)?).map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+serde_as_u8!(Cidr);
+serde_as_u8!(Ipv6Cidr);
+serde_as_u8!(Prefix);
+
+#[derive(Serialize, Deserialize)]
+struct SlsmFields {
+    base_network: Network,
+    cidr: Cidr,
+}
+
+impl Serialize for Slsm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SlsmFields {
+            base_network: self.base_network().clone(),
+            cidr: self.cidr(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Slsm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = SlsmFields::deserialize(deserializer)?;
+        Slsm::new(fields.base_network, fields.cidr).map_err(D::Error::custom)
+    }
+}
+
+// Vlsms built from bare host counts are written as the list of required hosts, and Vlsms with labels, metadata,
+// CIDRs, pinned networks, growth or reserved buddies as the list of requirements.
+// The allocated subnets are written in the order of the requirements, so they are restored whatever placement
+// created them. Without them the subnets are planned again with first-fit placement.
+#[derive(Serialize, Deserialize)]
+struct VlsmFields {
+    base_network: Network,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    required_hosts: Option<Vec<u32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    requirements: Option<Vec<Requirement>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    subnets: Option<Vec<Network>>,
+}
+
+impl Serialize for Vlsm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let unlabelled = self.requirements().iter().all(|requirement| {
+            requirement.label().is_empty()
+                && requirement.metadata().is_empty()
+                && requirement.host_count().is_some()
+                && requirement.growth().is_none()
+                && !requirement.reserves_buddy()
+        });
+        let (required_hosts, requirements) = if unlabelled {
+            let required_hosts = self
+                .requirements()
+                .iter()
+                .filter_map(|requirement| requirement.host_count())
+                .collect();
+            (Some(required_hosts), None)
+        } else {
+            (None, Some(self.requirements().to_vec()))
+        };
+        let subnets = self
+            .allocations_in_input_order()
+            .into_iter()
+            .map(|allocation| allocation.network().clone())
+            .collect();
+        VlsmFields {
+            base_network: self.base_network().clone(),
+            required_hosts,
+            requirements,
+            subnets: Some(subnets),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Vlsm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = VlsmFields::deserialize(deserializer)?;
+        let requirements = match (fields.required_hosts, fields.requirements) {
+            (Some(required_hosts), None) => required_hosts
+                .into_iter()
+                .map(|hosts| Requirement::hosts("", hosts))
+                .collect(),
+            (None, Some(requirements)) => requirements,
+            _ => {
+                return Err(D::Error::custom(
+                    "expected exactly one of required_hosts or requirements",
+                ))
+            }
+        };
+        let Some(subnets) = fields.subnets else {
+            return Vlsm::with_requirements(fields.base_network, requirements)
+                .map_err(D::Error::custom);
+        };
+
+        if subnets.len() != requirements.len() {
+            return Err(D::Error::invalid_length(
+                subnets.len(),
+                &"a subnet for each requirement",
+            ));
+        }
+        for (index, (requirement, subnet)) in requirements.iter().zip(subnets.iter()).enumerate() {
+            let matches = match requirement.kind() {
+                RequirementKind::Pinned(network) => subnet == network,
+                _ => requirement.required_cidr().map_err(D::Error::custom)? == subnet.cidr(),
+            };
+            if !matches {
+                return Err(D::Error::custom(format!(
+                    "subnet {subnet} does not match requirement {index}"
+                )));
+            }
+        }
+        Vlsm::with_subnets(fields.base_network, requirements, &subnets).map_err(D::Error::custom)
+    }
+}
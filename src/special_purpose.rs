@@ -0,0 +1,214 @@
+#![warn(missing_docs)]
+
+use std::fmt;
+use std::net::Ipv4Addr;
+
+use crate::{Cidr, Network};
+
+/// The kind of use an IPv4 address is reserved for.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum AddressClass {
+    /// Private-use addresses (RFC 1918)
+    Private,
+    /// Shared address space for carrier-grade NAT (RFC 6598)
+    SharedAddressSpace,
+    /// Loopback addresses (RFC 1122)
+    Loopback,
+    /// Link-local addresses (RFC 3927)
+    LinkLocal,
+    /// Multicast addresses (RFC 5771)
+    Multicast,
+    /// Addresses reserved for documentation (RFC 5737)
+    Documentation,
+    /// Addresses reserved for benchmarking (RFC 2544)
+    Benchmarking,
+    /// Addresses reserved by protocol or for future use, which are not globally reachable
+    Reserved,
+    /// Globally reachable addresses
+    Global,
+}
+
+impl fmt::Display for AddressClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Private => "private",
+            Self::SharedAddressSpace => "shared address space",
+            Self::Loopback => "loopback",
+            Self::LinkLocal => "link-local",
+            Self::Multicast => "multicast",
+            Self::Documentation => "documentation",
+            Self::Benchmarking => "benchmarking",
+            Self::Reserved => "reserved",
+            Self::Global => "global",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The classification of an address, and the registry block it was taken from.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Classification {
+    class: AddressClass,
+    block: Option<Network>,
+    name: &'static str,
+    rfc: Option<&'static str>,
+}
+
+impl Classification {
+    /// Return the kind of use the address is reserved for.
+    pub fn class(&self) -> AddressClass {
+        self.class
+    }
+
+    /// Return the registry block containing the address, or None for an address outside every block.
+    pub fn block(&self) -> Option<&Network> {
+        self.block.as_ref()
+    }
+
+    /// Return the name of the registry block, e.g. "Private-Use".
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Return the RFC defining the registry block, e.g. "RFC 1918".
+    pub fn rfc(&self) -> Option<&'static str> {
+        self.rfc
+    }
+
+    /// Return the classification of addresses outside every registry block.
+    fn global_unicast() -> Self {
+        Self {
+            class: AddressClass::Global,
+            block: None,
+            name: "Global Unicast",
+            rfc: None,
+        }
+    }
+}
+
+/// Classify addresses according to the IANA special-purpose address registries.
+pub trait ClassifyAddress {
+    /// Return the classification from the most specific registry block containing the address.
+    fn classify(&self) -> Classification;
+}
+
+impl ClassifyAddress for Ipv4Addr {
+    fn classify(&self) -> Classification {
+        REGISTRY
+            .iter()
+            .filter(|entry| entry.network().contains(*self))
+            .max_by_key(|entry| entry.cidr)
+            .map(Entry::classification)
+            .unwrap_or_else(Classification::global_unicast)
+    }
+}
+
+/// Return the classification of every address in the network, as the minimal sorted list of
+/// Networks in which all addresses have the same classification.
+pub(crate) fn classify_network(network: &Network) -> Vec<(Network, Classification)> {
+    let mut parts = Vec::new();
+    classify_into(network.clone(), &mut parts);
+    parts
+}
+
+fn classify_into(network: Network, parts: &mut Vec<(Network, Classification)>) {
+    // A network straddles categories if a more specific block starts inside it, in which case each half is
+    // classified separately. Otherwise every address shares the classification of its first address.
+    let straddles = REGISTRY.iter().any(|entry| {
+        entry.cidr > *network.cidr() && network.contains(Ipv4Addr::from(entry.address()))
+    });
+    if !straddles {
+        let classification = network.network_id().classify();
+        parts.push((network, classification));
+        return;
+    }
+
+    let cidr = Cidr::new(*network.cidr() + 1).expect("a /32 contains no more specific block");
+    let lower = Network::new(network.network_id(), cidr).expect("Network::new never fails");
+    let upper = Network::new(Ipv4Addr::from(lower.last_address_u32() + 1), cidr)
+        .expect("Network::new never fails");
+    classify_into(lower, parts);
+    classify_into(upper, parts);
+}
+
+/// A row of the registry.
+struct Entry {
+    address: [u8; 4],
+    cidr: u8,
+    name: &'static str,
+    rfc: &'static str,
+    class: AddressClass,
+}
+
+impl Entry {
+    fn address(&self) -> u32 {
+        u32::from(Ipv4Addr::from(self.address))
+    }
+
+    fn network(&self) -> Network {
+        Network::new(
+            Ipv4Addr::from(self.address),
+            Cidr::new(self.cidr).expect("registry cidr is at most 32"),
+        )
+        .expect("Network::new never fails")
+    }
+
+    fn classification(&self) -> Classification {
+        Classification {
+            class: self.class,
+            block: Some(self.network()),
+            name: self.name,
+            rfc: Some(self.rfc),
+        }
+    }
+}
+
+const fn entry(
+    address: [u8; 4],
+    cidr: u8,
+    name: &'static str,
+    rfc: &'static str,
+    class: AddressClass,
+) -> Entry {
+    Entry {
+        address,
+        cidr,
+        name,
+        rfc,
+        class,
+    }
+}
+
+/// The IANA IPv4 Special-Purpose Address Registry, with the multicast block and its documentation block from the
+/// IPv4 Multicast Address Space Registry. Blocks marked as globally reachable in the registry are classed as Global.
+#[rustfmt::skip]
+const REGISTRY: &[Entry] = &[
+    entry([0, 0, 0, 0], 8, "This network", "RFC 791", AddressClass::Reserved),
+    entry([0, 0, 0, 0], 32, "This host on this network", "RFC 1122", AddressClass::Reserved),
+    entry([10, 0, 0, 0], 8, "Private-Use", "RFC 1918", AddressClass::Private),
+    entry([100, 64, 0, 0], 10, "Shared Address Space", "RFC 6598", AddressClass::SharedAddressSpace),
+    entry([127, 0, 0, 0], 8, "Loopback", "RFC 1122", AddressClass::Loopback),
+    entry([169, 254, 0, 0], 16, "Link Local", "RFC 3927", AddressClass::LinkLocal),
+    entry([172, 16, 0, 0], 12, "Private-Use", "RFC 1918", AddressClass::Private),
+    entry([192, 0, 0, 0], 24, "IETF Protocol Assignments", "RFC 6890", AddressClass::Reserved),
+    entry([192, 0, 0, 0], 29, "IPv4 Service Continuity Prefix", "RFC 7335", AddressClass::Reserved),
+    entry([192, 0, 0, 8], 32, "IPv4 dummy address", "RFC 7600", AddressClass::Reserved),
+    entry([192, 0, 0, 9], 32, "Port Control Protocol Anycast", "RFC 7723", AddressClass::Global),
+    entry([192, 0, 0, 10], 32, "Traversal Using Relays around NAT Anycast", "RFC 8155", AddressClass::Global),
+    entry([192, 0, 0, 170], 32, "NAT64/DNS64 Discovery", "RFC 8880", AddressClass::Reserved),
+    entry([192, 0, 0, 171], 32, "NAT64/DNS64 Discovery", "RFC 8880", AddressClass::Reserved),
+    entry([192, 0, 2, 0], 24, "Documentation (TEST-NET-1)", "RFC 5737", AddressClass::Documentation),
+    entry([192, 31, 196, 0], 24, "AS112-v4", "RFC 7535", AddressClass::Global),
+    entry([192, 52, 193, 0], 24, "AMT", "RFC 7450", AddressClass::Global),
+    entry([192, 88, 99, 0], 24, "Deprecated (6to4 Relay Anycast)", "RFC 7526", AddressClass::Reserved),
+    entry([192, 88, 99, 2], 32, "6a44-relay anycast address", "RFC 6751", AddressClass::Global),
+    entry([192, 168, 0, 0], 16, "Private-Use", "RFC 1918", AddressClass::Private),
+    entry([192, 175, 48, 0], 24, "Direct Delegation AS112 Service", "RFC 7534", AddressClass::Global),
+    entry([198, 18, 0, 0], 15, "Benchmarking", "RFC 2544", AddressClass::Benchmarking),
+    entry([198, 51, 100, 0], 24, "Documentation (TEST-NET-2)", "RFC 5737", AddressClass::Documentation),
+    entry([203, 0, 113, 0], 24, "Documentation (TEST-NET-3)", "RFC 5737", AddressClass::Documentation),
+    entry([224, 0, 0, 0], 4, "Multicast", "RFC 5771", AddressClass::Multicast),
+    entry([233, 252, 0, 0], 24, "MCAST-TEST-NET", "RFC 6676", AddressClass::Documentation),
+    entry([240, 0, 0, 0], 4, "Reserved", "RFC 1112", AddressClass::Reserved),
+    entry([255, 255, 255, 255], 32, "Limited Broadcast", "RFC 8190", AddressClass::Reserved),
+];
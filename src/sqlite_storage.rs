@@ -0,0 +1,92 @@
+#![warn(missing_docs)]
+
+use std::path::Path;
+use std::time::Duration;
+
+use rusqlite::{params, Connection, TransactionBehavior};
+
+use crate::ledger::{LedgerError, Storage};
+use crate::Network;
+
+/// Storage that keeps allocations in an SQLite database, in an "allocations" table with a row for each
+/// allocated network. Updates run in an immediate transaction, so concurrent processes take turns.
+#[derive(Debug)]
+pub struct SqliteStorage {
+    connection: Connection,
+}
+
+impl SqliteStorage {
+    /// Open the database at path, creating it and the allocations table if they do not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, LedgerError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Open a private in-memory database.
+    pub fn open_in_memory() -> Result<Self, LedgerError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(connection: Connection) -> Result<Self, LedgerError> {
+        // Wait for other processes' transactions to finish rather than failing straight away.
+        connection.busy_timeout(Duration::from_secs(30))?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS allocations (
+                base_network TEXT NOT NULL,
+                network TEXT NOT NULL,
+                PRIMARY KEY (base_network, network)
+            )",
+        )?;
+        Ok(Self { connection })
+    }
+}
+
+fn select(connection: &Connection, base_network: &Network) -> Result<Vec<Network>, LedgerError> {
+    let mut statement =
+        connection.prepare("SELECT network FROM allocations WHERE base_network = ?1")?;
+    let rows = statement.query_map(params![base_network.to_string()], |row| {
+        row.get::<_, String>(0)
+    })?;
+
+    let mut networks = Vec::new();
+    for row in rows {
+        let row = row?;
+        let network =
+            Network::try_from(row.as_str()).map_err(|_| LedgerError::InvalidRecord(row))?;
+        networks.push(network);
+    }
+    networks.sort();
+    Ok(networks)
+}
+
+impl Storage for SqliteStorage {
+    fn load(&self, base_network: &Network) -> Result<Vec<Network>, LedgerError> {
+        select(&self.connection, base_network)
+    }
+
+    fn update<T, F>(&mut self, base_network: &Network, f: F) -> Result<T, LedgerError>
+    where
+        F: FnOnce(&mut Vec<Network>) -> Result<T, LedgerError>,
+    {
+        let transaction = self
+            .connection
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        let mut networks = select(&transaction, base_network)?;
+        let result = f(&mut networks)?;
+
+        let base_network = base_network.to_string();
+        transaction.execute(
+            "DELETE FROM allocations WHERE base_network = ?1",
+            params![base_network],
+        )?;
+        {
+            let mut statement = transaction
+                .prepare("INSERT INTO allocations (base_network, network) VALUES (?1, ?2)")?;
+            for network in networks.iter() {
+                statement.execute(params![base_network, network.to_string()])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(result)
+    }
+}
@@ -1,18 +1,22 @@
 #![warn(missing_docs)]
 
 use std::cmp::Reverse;
-use std::net::Ipv4Addr;
+use std::iter::FusedIterator;
+use std::net::{Ipv4Addr, Ipv6Addr};
 
-use crate::{Cidr, Network, NetworkError};
+use crate::{
+    Allocation, Cidr, FirstFit, IpNetwork, Ipv6Cidr, Ipv6Network, Network, NetworkError, Placement,
+    Pool, Prefix, Requirement, RequirementKind, Subnetting, VlsmReport,
+};
 
 ///Partitions a Network into subnets by using a Single Length Subnet Mask
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Slsm {
     base_network: Network,
     cidr: Cidr,
-    number_of_subnet_bits: u8,
-    length: u32,
-    current_subnet: u32,
+    length: u64,
+    current_subnet: u64,
+    end_subnet: u64,
 }
 
 impl Slsm {
@@ -23,15 +27,16 @@ impl Slsm {
             return Err(NetworkError::InvalidSubnetCidr);
         }
 
+        // Splitting a /0 into /32s gives 2^32 subnets, one more than a u32 can count.
         let number_of_subnet_bits = *cidr - *base_network.cidr();
-        let length = 2u32.pow(number_of_subnet_bits as u32);
+        let length = 1_u64 << number_of_subnet_bits;
 
         Ok(Self {
             base_network,
             cidr,
-            number_of_subnet_bits,
             length,
             current_subnet: 0,
+            end_subnet: length,
         })
     }
 
@@ -44,23 +49,185 @@ impl Slsm {
     pub fn cidr(&self) -> Cidr {
         self.cidr
     }
+
+    /// Return subnet number n, counting from 0, regardless of how far the iterator has advanced.
+    pub fn get(&self, n: u32) -> Option<Network> {
+        if n as u64 >= self.length {
+            return None;
+        }
+
+        let subnet_network_u32 = u32::from(self.base_network.network_id())
+            + n.checked_shl(self.subnet_host_bits()).unwrap_or(0);
+        Network::new(Ipv4Addr::from(subnet_network_u32), self.cidr).ok()
+    }
+
+    /// Return the number of the subnet equal to network, or None if network is not one of the subnets.
+    pub fn index_of(&self, network: &Network) -> Option<u32> {
+        if network.cidr() != self.cidr {
+            return None;
+        }
+        self.index_of_address(network.network_id())
+    }
+
+    /// Return the number of the subnet containing the address, or None if the address is outside the base network.
+    pub fn index_of_address(&self, ip_address: Ipv4Addr) -> Option<u32> {
+        if !self.base_network.contains(ip_address) {
+            return None;
+        }
+
+        let offset = u32::from(ip_address) - u32::from(self.base_network.network_id());
+        Some(offset.checked_shr(self.subnet_host_bits()).unwrap_or(0))
+    }
+
+    /// Return the number of host bits in each subnet.
+    fn subnet_host_bits(&self) -> u32 {
+        32 - *self.cidr as u32
+    }
 }
 
 impl Iterator for Slsm {
     type Item = Network;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current_subnet >= self.length {
+        self.nth(0)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n as u64 >= self.end_subnet - self.current_subnet {
+            self.current_subnet = self.end_subnet;
             return None;
         }
 
-        let subnet_network_u32 = u32::from(self.base_network.network_id())
-            + (self.current_subnet
-                << (32 - *self.base_network.cidr() - self.number_of_subnet_bits));
+        let index = self.current_subnet + n as u64;
+        self.current_subnet = index + 1;
+        self.get(index as u32)
+    }
 
-        self.current_subnet += 1;
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.end_subnet - self.current_subnet) {
+            Ok(remaining) => (remaining, Some(remaining)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
 
-        Network::new(Ipv4Addr::from(subnet_network_u32), self.cidr).ok()
+    fn count(self) -> usize {
+        usize::try_from(self.end_subnet - self.current_subnet).unwrap_or(usize::MAX)
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for Slsm {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.nth_back(0)
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if n as u64 >= self.end_subnet - self.current_subnet {
+            self.end_subnet = self.current_subnet;
+            return None;
+        }
+
+        let index = self.end_subnet - 1 - n as u64;
+        self.end_subnet = index;
+        self.get(index as u32)
+    }
+}
+
+/// Only implemented where a usize can hold the 2^32 subnets of a /0 split into /32s.
+/// Use subnet_count for the exact number of subnets on every target.
+#[cfg(target_pointer_width = "64")]
+impl ExactSizeIterator for Slsm {}
+
+impl FusedIterator for Slsm {}
+
+impl Subnetting for Slsm {
+    type Subnets = Slsm;
+
+    fn base_network(&self) -> &Network {
+        &self.base_network
+    }
+
+    fn subnets(&self) -> Self::Subnets {
+        Self {
+            current_subnet: 0,
+            end_subnet: self.length,
+            ..self.clone()
+        }
+    }
+
+    fn subnet_count(&self) -> u64 {
+        self.length
+    }
+
+    fn used_addresses(&self) -> u64 {
+        self.base_network.number_of_addresses()
+    }
+}
+
+///Partitions an Ipv6Network into subnets by using a Single Length Subnet Mask
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Ipv6Slsm {
+    base_network: Ipv6Network,
+    cidr: Ipv6Cidr,
+    last_subnet: u128,
+    current_subnet: Option<u128>,
+}
+
+impl Ipv6Slsm {
+    /// Create and initialise a new Ipv6Slsm struct. Takes an IPv6 network and a new CIDR value.
+    /// Return an iterator over the resulting slsm subnets.
+    pub fn new(base_network: Ipv6Network, cidr: Ipv6Cidr) -> Result<Self, NetworkError> {
+        if base_network.cidr() > cidr {
+            return Err(NetworkError::InvalidSubnetCidr);
+        }
+
+        // Splitting a /0 into /128s gives 2^128 subnets, so track the index of the last subnet
+        // rather than the number of subnets.
+        let number_of_subnet_bits = *cidr - *base_network.cidr();
+        let last_subnet = u128::MAX
+            .checked_shr(128 - number_of_subnet_bits as u32)
+            .unwrap_or(0);
+
+        Ok(Self {
+            base_network,
+            cidr,
+            last_subnet,
+            current_subnet: Some(0),
+        })
+    }
+
+    /// Return the base network from the Subnet.
+    pub fn base_network(&self) -> &Ipv6Network {
+        &self.base_network
+    }
+
+    /// Return the cidr being applied to the base network.
+    pub fn cidr(&self) -> Ipv6Cidr {
+        self.cidr
+    }
+}
+
+impl Iterator for Ipv6Slsm {
+    type Item = Ipv6Network;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current_subnet = self.current_subnet?;
+
+        let subnet_network_u128 = u128::from(self.base_network.network_id())
+            + current_subnet
+                .checked_shl(128 - *self.cidr as u32)
+                .unwrap_or(0);
+
+        self.current_subnet = if current_subnet < self.last_subnet {
+            Some(current_subnet + 1)
+        } else {
+            None
+        };
+
+        Ipv6Network::new(Ipv6Addr::from(subnet_network_u128), self.cidr).ok()
     }
 }
 
@@ -68,27 +235,95 @@ impl Iterator for Slsm {
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Vlsm {
     base_network: Network,
+    requirements: Vec<Requirement>,
     required_hosts: Vec<u32>,
+    allocations: Vec<Allocation>,
     current_subnet: usize,
-    next_network_id: Ipv4Addr,
 }
 
 impl Vlsm {
     /// Create and initialise a new Vlsm struct. Takes a network and list of required hosts numbers per subnet.
     /// Return an iterator over the resulting subnets. The iterator will return the subnets in the order of greatest number of hosts to the smallest.
-    /// It will stop returning Networks when it reaches the end of the required number of hosts, or it does not have enough ip addresses to allocate the next subnet.
+    /// Return a HostCountOutOfRange error if a required number of hosts does not fit in any network,
+    /// or an InsufficientAddressSpace error if the required hosts do not all fit in the base network.
     pub fn new(base_network: Network, required_hosts: Vec<u32>) -> Result<Self, NetworkError> {
-        let mut required_hosts = required_hosts;
-        required_hosts.sort_by_key(|c| Reverse(*c));
+        let requirements = required_hosts
+            .into_iter()
+            .map(|hosts| Requirement::hosts("", hosts))
+            .collect();
+        Vlsm::with_requirements(base_network, requirements)
+    }
 
-        let next_network_id = base_network.network_id();
+    /// Create and initialise a new Vlsm struct from labelled requirements, which may ask for a number of hosts,
+    /// a CIDR or a pinned network. Pinned networks are reserved first and the other requirements are allocated
+    /// around them in the same order and with the same errors as the required hosts given to new.
+    /// Return a NetworkOutsideBase error if a pinned network is not inside the base network,
+    /// an AllocationConflict error if two pinned networks overlap, or a FragmentedAddressSpace error
+    /// if the pinned networks leave no free block large enough for a requirement.
+    pub fn with_requirements(
+        base_network: Network,
+        requirements: Vec<Requirement>,
+    ) -> Result<Self, NetworkError> {
+        Vlsm::with_placement(base_network, requirements, &FirstFit)
+    }
 
-        Ok(Self {
+    /// Create and initialise a new Vlsm struct from requirements like with_requirements, using the placement
+    /// strategy to choose where in the free space each subnet is allocated.
+    pub fn with_placement(
+        base_network: Network,
+        requirements: Vec<Requirement>,
+        placement: &dyn Placement,
+    ) -> Result<Self, NetworkError> {
+        let mut pool = Pool::new(base_network.clone());
+        let allocations = plan(&mut pool, &requirements, placement)?;
+        Ok(Vlsm::from_allocations(
+            base_network,
+            requirements,
+            allocations,
+        ))
+    }
+
+    /// Create and initialise a Vlsm struct from the subnet previously allocated for each requirement,
+    /// given in the order of the requirements, instead of planning the subnets again.
+    #[cfg(feature = "serde")]
+    pub(crate) fn with_subnets(
+        base_network: Network,
+        requirements: Vec<Requirement>,
+        subnets: &[Network],
+    ) -> Result<Self, NetworkError> {
+        let mut pool = Pool::new(base_network.clone());
+        let allocations = restore(&mut pool, &requirements, subnets)?;
+        Ok(Vlsm::from_allocations(
+            base_network,
+            requirements,
+            allocations,
+        ))
+    }
+
+    fn from_allocations(
+        base_network: Network,
+        requirements: Vec<Requirement>,
+        allocations: Vec<Allocation>,
+    ) -> Self {
+        let required_hosts = allocations
+            .iter()
+            .map(|allocation| {
+                allocation
+                    .requirement()
+                    .planned_host_count()
+                    .map_or(allocation.network().number_of_hosts() as u32, |hosts| {
+                        hosts as u32
+                    })
+            })
+            .collect();
+
+        Self {
             base_network,
+            requirements,
             required_hosts,
+            allocations,
             current_subnet: 0,
-            next_network_id,
-        })
+        }
     }
 
     /// Return the base network from the Subnet.
@@ -96,44 +331,414 @@ impl Vlsm {
         &self.base_network
     }
 
-    /// Returns the required Cidr to accommodate the required number of hosts.
-    fn required_cidr_for_host_count(hosts: u32) -> Result<Cidr, NetworkError> {
-        let required_cidr = 32 - (((hosts+2) as f32).log2().ceil()) as u8;
-        Cidr::new(required_cidr)
+    /// Return the required number of hosts per subnet in allocation order, pinned networks first and then
+    /// from greatest to smallest. Growth is included, and CIDR and pinned requirements count every host of their subnet.
+    pub fn required_hosts(&self) -> &[u32] {
+        &self.required_hosts
+    }
+
+    /// Return the requirements in the order they were given.
+    pub fn requirements(&self) -> &[Requirement] {
+        &self.requirements
+    }
+
+    /// Return the subnet allocated for each requirement, in the order the subnets are allocated.
+    pub fn allocations(&self) -> &[Allocation] {
+        &self.allocations
+    }
+
+    /// Return the subnet allocated for each requirement, in the order the requirements were given.
+    pub fn allocations_in_input_order(&self) -> Vec<&Allocation> {
+        let mut allocations: Vec<&Allocation> = self.allocations.iter().collect();
+        allocations.sort_by_key(|allocation| allocation.index());
+        allocations
+    }
+
+    /// Return a report of the subnets allocated from the base network, the free space left over,
+    /// and the addresses wasted by rounding.
+    pub fn report(&self) -> VlsmReport {
+        VlsmReport::new(self.base_network.clone(), &self.allocations)
     }
 }
 
+/// Allocate a subnet from the pool for each requirement, returning the allocations in allocation order.
+/// Return an InsufficientAddressSpace error if the requirements need more addresses than are free,
+/// or a FragmentedAddressSpace error if no free block is large enough for a requirement.
+/// Pinned networks are allocated first, then the other requirements from largest to smallest,
+/// each where the placement strategy puts it. A requirement that reserves its buddy is placed as the block
+/// one prefix bit shorter, with the subnet in its lower half and the buddy in its upper half.
+pub(crate) fn plan(
+    pool: &mut Pool,
+    requirements: &[Requirement],
+    placement: &dyn Placement,
+) -> Result<Vec<Allocation>, NetworkError> {
+    let mut allocations = Vec::with_capacity(requirements.len());
+    let available = pool.free_addresses() as u128;
+    let mut required = 0;
+    for index in pinned_order(requirements) {
+        let requirement = &requirements[index];
+        if let RequirementKind::Pinned(network) = requirement.kind() {
+            let allocation = reserve(pool, index, requirement, network.clone())?;
+            required += allocation.network().number_of_addresses() as u128;
+            if let Some(buddy) = allocation.reserved_buddy() {
+                required += buddy.number_of_addresses() as u128;
+            }
+            allocations.push(allocation);
+        }
+    }
+
+    let order = sized_order(requirements)?;
+
+    // Subnets are allocated largest first, so on an empty base network each one starts on a boundary of its own
+    // size and the requirements fit as long as their total size does not exceed the base network.
+    let mut failed = None;
+    for sized in order.iter() {
+        required += 1_u128 << sized.block_bits;
+        if required > available && failed.is_none() {
+            failed = Some((sized.index, sized.hosts));
+        }
+    }
+
+    if let Some((index, hosts)) = failed {
+        return Err(NetworkError::InsufficientAddressSpace {
+            index,
+            hosts,
+            required,
+            available,
+        });
+    }
+
+    for sized in order {
+        // Pinned networks can leave the free space too fragmented for a subnet that fits by size alone.
+        let block_cidr = Cidr::new((32 - sized.block_bits) as u8)?;
+        let Some(block) = placement.place(pool.free(), block_cidr) else {
+            return Err(NetworkError::FragmentedAddressSpace {
+                index: sized.index,
+                hosts: sized.hosts,
+                cidr: *block_cidr,
+            });
+        };
+        let network = Network::new(block.network_id(), sized.cidr)?;
+        allocations.push(reserve(
+            pool,
+            sized.index,
+            &requirements[sized.index],
+            network,
+        )?);
+    }
+    Ok(allocations)
+}
+
+/// Allocate the given subnet from the pool for each requirement in the same order as plan,
+/// returning the allocations in allocation order. The subnets are given in the order of the requirements
+/// and must each match their requirement.
+#[cfg(feature = "serde")]
+fn restore(
+    pool: &mut Pool,
+    requirements: &[Requirement],
+    subnets: &[Network],
+) -> Result<Vec<Allocation>, NetworkError> {
+    let sized = sized_order(requirements)?
+        .into_iter()
+        .map(|sized| sized.index);
+    pinned_order(requirements)
+        .chain(sized)
+        .map(|index| reserve(pool, index, &requirements[index], subnets[index].clone()))
+        .collect()
+}
+
+/// Return the indices of the pinned requirements, in input order.
+fn pinned_order(requirements: &[Requirement]) -> impl Iterator<Item = usize> + '_ {
+    requirements
+        .iter()
+        .enumerate()
+        .filter(|(_, requirement)| matches!(requirement.kind(), RequirementKind::Pinned(_)))
+        .map(|(index, _)| index)
+}
+
+/// A requirement that is not pinned, with the size of the subnet and block it needs.
+struct SizedRequirement {
+    /// The position of the requirement in the list of requirements
+    index: usize,
+    /// The CIDR of the subnet
+    cidr: Cidr,
+    /// The number of host bits of the block the subnet is placed in, one more than the subnet's with a buddy
+    block_bits: u32,
+    /// The number of hosts planned for, including growth
+    planned: u64,
+    /// The number of hosts to report if the requirement does not fit
+    hosts: u32,
+}
+
+/// Return the requirements that are not pinned in allocation order, largest block first.
+fn sized_order(requirements: &[Requirement]) -> Result<Vec<SizedRequirement>, NetworkError> {
+    let mut order = Vec::with_capacity(requirements.len());
+    for (index, requirement) in requirements.iter().enumerate() {
+        if let RequirementKind::Pinned(_) = requirement.kind() {
+            continue;
+        }
+        let cidr = requirement.required_cidr()?;
+        let subnet_hosts = Network::new(Ipv4Addr::UNSPECIFIED, cidr)?.number_of_hosts();
+        let planned = requirement.planned_host_count().unwrap_or(subnet_hosts);
+        let hosts = requirement.host_count().unwrap_or(subnet_hosts as u32);
+        let block_bits = 32 - *cidr as u32 + requirement.reserves_buddy() as u32;
+        order.push(SizedRequirement {
+            index,
+            cidr,
+            block_bits,
+            planned,
+            hosts,
+        });
+    }
+    order.sort_by_key(|sized| (Reverse(sized.block_bits), Reverse(sized.planned)));
+    Ok(order)
+}
+
+/// Allocate the subnet for the requirement from the pool, along with its buddy if the requirement reserves one.
+/// Return a NetworkOutsideBase error if the buddy is not inside the base network, as for a /0,
+/// otherwise the errors of Pool::allocate_specific.
+fn reserve(
+    pool: &mut Pool,
+    index: usize,
+    requirement: &Requirement,
+    network: Network,
+) -> Result<Allocation, NetworkError> {
+    pool.allocate_specific(network.clone())?;
+    let buddy = if requirement.reserves_buddy() {
+        let buddy = network.buddy().ok_or(NetworkError::NetworkOutsideBase {
+            network: network.clone(),
+        })?;
+        pool.allocate_specific(buddy.clone())?;
+        Some(buddy)
+    } else {
+        None
+    };
+    Ok(Allocation::new(index, requirement.clone(), network, buddy))
+}
+
 impl Iterator for Vlsm {
     type Item = Network;
 
+    fn next(&mut self) -> Option<Self::Item> {
+        let allocation = self.allocations.get(self.current_subnet)?;
+        self.current_subnet += 1;
+        Some(allocation.network().clone())
+    }
+}
+
+impl Subnetting for Vlsm {
+    type Subnets = Vlsm;
+
+    fn base_network(&self) -> &Network {
+        &self.base_network
+    }
+
+    fn subnets(&self) -> Self::Subnets {
+        Self {
+            current_subnet: 0,
+            ..self.clone()
+        }
+    }
+
+    fn subnet_count(&self) -> u64 {
+        self.allocations.len() as u64
+    }
+
+    /// Buddy blocks reserved for growth are set aside rather than wasted, so they are not counted.
+    fn wasted_addresses(&self) -> u64 {
+        let reserved: u64 = self
+            .allocations
+            .iter()
+            .filter_map(Allocation::reserved_buddy)
+            .map(Network::number_of_addresses)
+            .sum();
+        self.base_network.number_of_addresses() - self.used_addresses() - reserved
+    }
+}
+
+///Partitions an Ipv6Network into subnets by using a Variable Length Subnet Mask.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Ipv6Vlsm {
+    base_network: Ipv6Network,
+    required_hosts: Vec<u32>,
+    current_subnet: usize,
+    next_network_id: Option<Ipv6Addr>,
+}
+
+impl Ipv6Vlsm {
+    /// Create and initialise a new Ipv6Vlsm struct. Takes an IPv6 network and list of required hosts numbers per subnet.
+    /// Return an iterator over the resulting subnets. The iterator will return the subnets in the order of greatest number of hosts to the smallest.
+    /// Return an InsufficientAddressSpace error if the required hosts do not all fit in the base network.
+    pub fn new(base_network: Ipv6Network, required_hosts: Vec<u32>) -> Result<Self, NetworkError> {
+        let mut required_hosts: Vec<(usize, u32)> =
+            required_hosts.into_iter().enumerate().collect();
+        required_hosts.sort_by_key(|(_, hosts)| Reverse(*hosts));
+
+        // A /0 base network holds 2^128 addresses, more than a u128 can count, but no list of
+        // u32 host counts can add up to that many.
+        let available = base_network.number_of_addresses().unwrap_or(u128::MAX);
+        let mut required = 0;
+        let mut failed = None;
+        for (index, hosts) in required_hosts.iter() {
+            let cidr = Ipv6Vlsm::required_cidr_for_host_count(*hosts)?;
+            required += 1_u128 << (128 - *cidr as u32);
+            if required > available && failed.is_none() {
+                failed = Some((*index, *hosts));
+            }
+        }
+        if let Some((index, hosts)) = failed {
+            return Err(NetworkError::InsufficientAddressSpace {
+                index,
+                hosts,
+                required,
+                available,
+            });
+        }
+
+        let required_hosts = required_hosts.into_iter().map(|(_, hosts)| hosts).collect();
+
+        let next_network_id = Some(base_network.network_id());
+
+        Ok(Self {
+            base_network,
+            required_hosts,
+            current_subnet: 0,
+            next_network_id,
+        })
+    }
+
+    /// Return the base network from the Subnet.
+    pub fn base_network(&self) -> &Ipv6Network {
+        &self.base_network
+    }
+
+    /// Returns the required Ipv6Cidr to accommodate the required number of hosts.
+    /// IPv6 has no broadcast address, so every address in the subnet is available to hosts.
+    fn required_cidr_for_host_count(hosts: u32) -> Result<Ipv6Cidr, NetworkError> {
+        let host_bits = (hosts as u64).next_power_of_two().trailing_zeros() as u8;
+        Ipv6Cidr::new(128 - host_bits)
+    }
+}
+
+impl Iterator for Ipv6Vlsm {
+    type Item = Ipv6Network;
+
     fn next(&mut self) -> Option<Self::Item> {
         if self.current_subnet >= self.required_hosts.len() {
             return None;
         }
 
         // If we have left the range of the base network, stop iterating.
-        if u32::from(self.next_network_id) >= u32::from(self.base_network.broadcast_address()?) {
-            return None;
-        }
+        let next_network_id = self.next_network_id?;
 
         let required_cidr =
-            Vlsm::required_cidr_for_host_count(self.required_hosts[self.current_subnet as usize])
+            Ipv6Vlsm::required_cidr_for_host_count(self.required_hosts[self.current_subnet])
                 .ok()?;
 
-        // If this overflows then the required number of hosts will not fit in the available space.
-        let number_of_subnet_bits = required_cidr.checked_sub( *self.base_network.cidr())?;
-
-        //The next network to bbe returned from the iterator
-        let result = Network::new(self.next_network_id, required_cidr).ok();
+        // The required number of hosts will not fit in the base network.
+        if required_cidr < self.base_network.cidr() {
+            return None;
+        }
 
+        // The next network to be returned from the iterator
+        let result = Ipv6Network::new(next_network_id, required_cidr).ok()?;
 
-        //Calculate the next network id.
-        let subnet_network_u32 = u32::from(self.next_network_id)
-            + (1 << (32 - *self.base_network.cidr() - number_of_subnet_bits ));
-        self.next_network_id = Ipv4Addr::from(subnet_network_u32);
+        // Calculate the next network id.
+        self.next_network_id = if result.last_address() < self.base_network.last_address() {
+            Some(Ipv6Addr::from(u128::from(result.last_address()) + 1))
+        } else {
+            None
+        };
 
         self.current_subnet += 1;
 
-        result
+        Some(result)
+    }
+}
+
+///Partitions an IpNetwork of either address family into subnets by using a Single Length Subnet Mask
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum IpSlsm {
+    /// Partitions an IPv4 Network
+    V4(Slsm),
+    /// Partitions an IPv6 Network
+    V6(Ipv6Slsm),
+}
+
+impl IpSlsm {
+    /// Create and initialise a new IpSlsm. Takes a network and a new Prefix value.
+    /// Return an iterator over the resulting slsm subnets.
+    pub fn new(base_network: IpNetwork, prefix: Prefix) -> Result<Self, NetworkError> {
+        match base_network {
+            IpNetwork::V4(network) => Ok(Self::V4(Slsm::new(network, prefix.try_into()?)?)),
+            IpNetwork::V6(network) => Ok(Self::V6(Ipv6Slsm::new(network, prefix.try_into()?)?)),
+        }
+    }
+
+    /// Return the base network from the Subnet.
+    pub fn base_network(&self) -> IpNetwork {
+        match self {
+            Self::V4(slsm) => IpNetwork::V4(slsm.base_network().clone()),
+            Self::V6(slsm) => IpNetwork::V6(slsm.base_network().clone()),
+        }
+    }
+
+    /// Return the prefix being applied to the base network.
+    pub fn prefix(&self) -> Prefix {
+        match self {
+            Self::V4(slsm) => slsm.cidr().into(),
+            Self::V6(slsm) => slsm.cidr().into(),
+        }
+    }
+}
+
+impl Iterator for IpSlsm {
+    type Item = IpNetwork;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::V4(slsm) => slsm.next().map(IpNetwork::V4),
+            Self::V6(slsm) => slsm.next().map(IpNetwork::V6),
+        }
+    }
+}
+
+///Partitions an IpNetwork of either address family into subnets by using a Variable Length Subnet Mask.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum IpVlsm {
+    /// Partitions an IPv4 Network
+    V4(Vlsm),
+    /// Partitions an IPv6 Network
+    V6(Ipv6Vlsm),
+}
+
+impl IpVlsm {
+    /// Create and initialise a new IpVlsm. Takes a network and list of required hosts numbers per subnet.
+    /// Return an iterator over the resulting subnets. The iterator will return the subnets in the order of greatest number of hosts to the smallest.
+    /// Return an InsufficientAddressSpace error if the required hosts do not all fit in the base network.
+    pub fn new(base_network: IpNetwork, required_hosts: Vec<u32>) -> Result<Self, NetworkError> {
+        match base_network {
+            IpNetwork::V4(network) => Ok(Self::V4(Vlsm::new(network, required_hosts)?)),
+            IpNetwork::V6(network) => Ok(Self::V6(Ipv6Vlsm::new(network, required_hosts)?)),
+        }
+    }
+
+    /// Return the base network from the Subnet.
+    pub fn base_network(&self) -> IpNetwork {
+        match self {
+            Self::V4(vlsm) => IpNetwork::V4(vlsm.base_network().clone()),
+            Self::V6(vlsm) => IpNetwork::V6(vlsm.base_network().clone()),
+        }
+    }
+}
+
+impl Iterator for IpVlsm {
+    type Item = IpNetwork;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::V4(vlsm) => vlsm.next().map(IpNetwork::V4),
+            Self::V6(vlsm) => vlsm.next().map(IpNetwork::V6),
+        }
     }
 }
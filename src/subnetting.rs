@@ -0,0 +1,32 @@
+#![warn(missing_docs)]
+
+use crate::Network;
+
+/// Describes a partition of a base Network into subnets, so that any partitioning strategy can be used generically.
+pub trait Subnetting {
+    /// Iterator over the subnets of the partition.
+    type Subnets: Iterator<Item = Network>;
+
+    /// Return the base network being partitioned.
+    fn base_network(&self) -> &Network;
+
+    /// Return an iterator over every subnet in the partition, starting from the first subnet.
+    fn subnets(&self) -> Self::Subnets;
+
+    /// Return the number of subnets in the partition.
+    fn subnet_count(&self) -> u64 {
+        self.subnets().count() as u64
+    }
+
+    /// Return the total number of addresses in the subnets of the partition.
+    fn used_addresses(&self) -> u64 {
+        self.subnets()
+            .map(|subnet| subnet.number_of_addresses())
+            .sum()
+    }
+
+    /// Return the number of addresses in the base network that are not part of any subnet.
+    fn wasted_addresses(&self) -> u64 {
+        self.base_network().number_of_addresses() - self.used_addresses()
+    }
+}
@@ -0,0 +1,107 @@
+#![warn(missing_docs)]
+
+use crate::{Allocation, IpSet, Network};
+
+/// Describes how a Vlsm uses its base network: the allocated subnets, the buddy blocks reserved next to them,
+/// the free space left over, and the addresses lost to rounding each subnet up to a power of two.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct VlsmReport {
+    base_network: Network,
+    allocated: Vec<Network>,
+    reserved: Vec<Network>,
+    free: Vec<Network>,
+    rounding_waste: u64,
+}
+
+impl VlsmReport {
+    /// Create a report from the subnets allocated for each requirement.
+    /// Only requirements for a number of hosts are counted in the rounding waste, including their growth.
+    pub(crate) fn new(base_network: Network, allocations: &[Allocation]) -> Self {
+        let mut allocated = Vec::new();
+        let mut reserved = Vec::new();
+        let mut rounding_waste = 0;
+        for allocation in allocations {
+            let subnet = allocation.network();
+            if let Some(hosts) = allocation.requirement().planned_host_count() {
+                rounding_waste += subnet.number_of_hosts() - hosts;
+            }
+            allocated.push(subnet.clone());
+            reserved.extend(allocation.reserved_buddy().cloned());
+        }
+
+        let free = IpSet::from(base_network.clone())
+            .difference(&allocated.iter().chain(reserved.iter()).cloned().collect())
+            .networks()
+            .collect();
+
+        Self {
+            base_network,
+            allocated,
+            reserved,
+            free,
+            rounding_waste,
+        }
+    }
+
+    /// Return the base network from the report.
+    pub fn base_network(&self) -> &Network {
+        &self.base_network
+    }
+
+    /// Return the allocated subnets, in the order they were allocated.
+    pub fn allocated(&self) -> &[Network] {
+        &self.allocated
+    }
+
+    /// Return the buddy blocks reserved next to allocated subnets, in the order the subnets were allocated.
+    pub fn reserved(&self) -> &[Network] {
+        &self.reserved
+    }
+
+    /// Return the free space left in the base network as the minimal sorted list of Networks.
+    pub fn free(&self) -> &[Network] {
+        &self.free
+    }
+
+    /// Return the largest free block, or None if the base network is full. Ties are broken by the lowest address.
+    /// Comparing it with the free addresses shows how fragmented the free space is.
+    pub fn largest_free_block(&self) -> Option<&Network> {
+        self.free
+            .iter()
+            .min_by_key(|block| (block.cidr(), block.network_id()))
+    }
+
+    /// Return the number of addresses in the allocated subnets.
+    pub fn allocated_addresses(&self) -> u64 {
+        self.allocated
+            .iter()
+            .map(|subnet| subnet.number_of_addresses())
+            .sum()
+    }
+
+    /// Return the number of addresses in the reserved buddy blocks.
+    pub fn reserved_addresses(&self) -> u64 {
+        self.reserved
+            .iter()
+            .map(|block| block.number_of_addresses())
+            .sum()
+    }
+
+    /// Return the number of addresses in the free space.
+    pub fn free_addresses(&self) -> u64 {
+        self.base_network.number_of_addresses()
+            - self.allocated_addresses()
+            - self.reserved_addresses()
+    }
+
+    /// Return the number of host addresses in the allocated subnets beyond the required number of hosts,
+    /// which are wasted by rounding each subnet up to a power of two.
+    pub fn rounding_waste(&self) -> u64 {
+        self.rounding_waste
+    }
+
+    /// Return the percentage of the base network's addresses that are in allocated subnets.
+    pub fn utilization(&self) -> f64 {
+        self.allocated_addresses() as f64 * 100.0 / self.base_network.number_of_addresses() as f64
+    }
+}